@@ -44,7 +44,7 @@ use std::str::FromStr;
 use std::{cmp, fmt};
 use unpremultiplytable::UNPREMULTIPLY_TABLE;
 use url::Url;
-use util::str::DOMString;
+use util::str::{DOMString, parse_color};
 use util::vec::byte_swap;
 
 #[must_root]
@@ -1328,20 +1328,6 @@ impl Drop for CanvasRenderingContext2D {
     }
 }
 
-pub fn parse_color(string: &str) -> Result<RGBA, ()> {
-    let mut parser = Parser::new(&string);
-    match CSSColor::parse(&mut parser) {
-        Ok(CSSColor::RGBA(rgba)) => {
-            if parser.is_exhausted() {
-                Ok(rgba)
-            } else {
-                Err(())
-            }
-        },
-        _ => Err(()),
-    }
-}
-
 // Used by drawImage to determine if a source or destination rectangle is valid
 // Origin coordinates and size cannot be negative. Size has to be greater than zero
 fn is_rect_valid(rect: Rect<f64>) -> bool {
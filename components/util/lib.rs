@@ -28,6 +28,7 @@ extern crate bitflags;
 #[macro_use]
 extern crate cssparser;
 extern crate euclid;
+extern crate fnv;
 extern crate getopts;
 extern crate heapsize;
 #[cfg(feature = "non-geckolib")]
@@ -52,6 +53,8 @@ extern crate selectors;
 extern crate serde;
 extern crate smallvec;
 extern crate string_cache;
+extern crate unicode_bidi;
+extern crate unicode_normalization;
 extern crate url;
 extern crate uuid;
 
@@ -5,45 +5,406 @@
 use app_units::Au;
 use cssparser::{self, Color, RGBA};
 use euclid::num::Zero;
+use fnv::FnvHasher;
+use heapsize::HeapSizeOf;
 use libc::c_char;
 use num_lib::ToPrimitive;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::ascii::AsciiExt;
 use std::borrow::ToOwned;
+use std::cell::Cell;
+use std::cmp::{self, Ordering};
+use std::collections::HashSet;
 use std::convert::AsRef;
 use std::ffi::CStr;
 use std::fmt;
-use std::iter::{Filter, Peekable};
+use std::hash::{Hash, Hasher};
+use std::iter::{Filter, FromIterator, Peekable};
 use std::ops::{Deref, DerefMut};
-use std::str::{Bytes, CharIndices, FromStr, Split, from_utf8};
+use std::str::{Bytes, CharIndices, Chars, FromStr, Lines, Split, from_utf8};
+use std::time::Duration;
+use unicode_bidi::{BidiClass, bidi_class};
+use unicode_normalization::UnicodeNormalization;
+use url::percent_encoding::{lossy_utf8_percent_decode, percent_decode};
 
-#[derive(Clone, Debug, Deserialize, Eq, Hash, HeapSizeOf, Ord, PartialEq, PartialOrd, Serialize)]
-pub struct DOMString(String);
+#[derive(Clone, Eq, Hash, HeapSizeOf, Ord, PartialEq, PartialOrd)]
+pub struct DOMString(String, IsAsciiCache);
 
 impl !Send for DOMString {}
 
+/// Caches whether the contents of a `DOMString` are entirely ASCII. The cache
+/// takes no part in comparisons or hashing.
+#[derive(Clone, Default)]
+struct IsAsciiCache(Cell<Option<bool>>);
+
+impl PartialEq for IsAsciiCache {
+    fn eq(&self, _: &IsAsciiCache) -> bool {
+        true
+    }
+}
+
+impl Eq for IsAsciiCache {}
+
+impl PartialOrd for IsAsciiCache {
+    fn partial_cmp(&self, _: &IsAsciiCache) -> Option<Ordering> {
+        Some(Ordering::Equal)
+    }
+}
+
+impl Ord for IsAsciiCache {
+    fn cmp(&self, _: &IsAsciiCache) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+impl Hash for IsAsciiCache {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+impl HeapSizeOf for IsAsciiCache {
+    fn heap_size_of_children(&self) -> usize {
+        0
+    }
+}
+
 impl DOMString {
     pub fn new() -> DOMString {
-        DOMString(String::new())
+        DOMString::from_string(String::new())
     }
     pub fn from_string(s: String) -> DOMString {
-        DOMString(s)
+        DOMString(s, IsAsciiCache::default())
     }
     // FIXME(ajeffrey): implement more of the String methods on DOMString?
     pub fn push_str(&mut self, string: &str) {
-        self.0.push_str(string)
+        self.string_mut().push_str(string)
     }
     pub fn clear(&mut self) {
-        self.0.clear()
+        self.string_mut().clear()
+    }
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+    pub fn shrink_to_fit(&mut self) {
+        // The contents are unchanged, so the cache remains valid.
+        self.0.shrink_to_fit()
+    }
+
+    /// Returns the underlying string for mutation, invalidating the cached
+    /// properties of its contents.
+    fn string_mut(&mut self) -> &mut String {
+        (self.1).0.set(None);
+        &mut self.0
+    }
+
+    /// Returns whether this string is entirely ASCII. The result is cached
+    /// until the string is next mutated.
+    pub fn is_ascii(&self) -> bool {
+        match (self.1).0.get() {
+            Some(is_ascii) => is_ascii,
+            None => {
+                let is_ascii = self.0.is_ascii();
+                (self.1).0.set(Some(is_ascii));
+                is_ascii
+            },
+        }
+    }
+
+    /// Inserts `string` at byte index `idx`. Panics if `idx` is out of bounds
+    /// or does not lie on a char boundary.
+    pub fn insert_str(&mut self, idx: usize, string: &str) {
+        let tail = self.0[idx..].to_owned();
+        let inner = self.string_mut();
+        inner.truncate(idx);
+        inner.reserve(string.len() + tail.len());
+        inner.push_str(string);
+        inner.push_str(&tail);
+    }
+
+    /// Removes the char at byte index `idx` and returns it. Panics if `idx` is
+    /// out of bounds or does not lie on a char boundary.
+    pub fn remove(&mut self, idx: usize) -> char {
+        self.string_mut().remove(idx)
     }
 
     pub fn bytes(&self) -> Bytes {
         self.0.bytes()
     }
+
+    /// Returns an iterator over the lines of this string, as per `str::lines`.
+    pub fn lines(&self) -> Lines {
+        self.0.lines()
+    }
+
+    /// Returns the number of lines of this string, as per `str::lines`.
+    pub fn line_count(&self) -> usize {
+        self.lines().count()
+    }
+
+    /// Returns the length of this string in UTF-16 code units, i.e. its
+    /// `length` as seen by script. For an ASCII string, that is its length in
+    /// bytes.
+    pub fn utf16_len(&self) -> usize {
+        if self.is_ascii() {
+            self.0.len()
+        } else {
+            utf16_len(&self.0)
+        }
+    }
+
+    /// Returns the slice of this string between the chars at indices `begin`
+    /// and `end`, or `None` if the range is invalid. An ASCII string is
+    /// sliced directly, since its char indices are byte indices.
+    pub fn char_slice(&self, begin: usize, end: usize) -> Option<&str> {
+        if !self.is_ascii() {
+            return slice_chars_checked(&self.0, begin, end);
+        }
+        if begin <= end && end <= self.0.len() {
+            Some(&self.0[begin..end])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the substring of at most `count` chars starting at char
+    /// `offset`, as `CharacterData.substringData()` does, or `None` if
+    /// `offset` is beyond the end of the string (when an `IndexSizeError`
+    /// should be thrown).
+    ///
+    /// https://dom.spec.whatwg.org/#concept-cd-substring
+    pub fn substring_data(&self, offset: usize, count: usize) -> Option<DOMString> {
+        let length = self.0.chars().count();
+        if offset > length {
+            return None;
+        }
+        let end = offset + cmp::min(count, length - offset);
+        Some(DOMString::from(slice_chars(&self.0, offset, end)))
+    }
+
+    /// Returns the number of occurrences of `c` in this string.
+    pub fn count_char(&self, c: char) -> usize {
+        if c.is_ascii() {
+            let byte = c as u8;
+            self.0.bytes().filter(|&b| b == byte).count()
+        } else {
+            self.0.chars().filter(|&ch| ch == c).count()
+        }
+    }
+
+    /// Returns whether serializing this string would escape any of its
+    /// characters, as `escape_html_attribute` does when `attribute_context`
+    /// is true, or `escape_html_text` otherwise.
+    pub fn needs_html_escaping(&self, attribute_context: bool) -> bool {
+        self.0.chars().any(|ch| match ch {
+            '&' | '<' | '>' | '\u{a0}' => true,
+            '"' => attribute_context,
+            _ => false,
+        })
+    }
+
+    /// Returns whether this string is a valid e-mail address.
+    ///
+    /// https://html.spec.whatwg.org/multipage/#valid-e-mail-address
+    pub fn is_valid_email(&self) -> bool {
+        is_valid_email(&self.0)
+    }
+
+    /// Returns whether this string is a valid e-mail address list, i.e. a
+    /// set of comma-separated valid e-mail addresses. The empty string is a
+    /// valid (empty) list.
+    ///
+    /// https://html.spec.whatwg.org/multipage/#valid-e-mail-address-list
+    pub fn is_valid_email_list(&self) -> bool {
+        self.0.is_empty() ||
+        self.0.split(',').all(|address| is_valid_email(address.trim_matches(WHITESPACE)))
+    }
+
+    /// Returns whether this string is equal to `other`, ignoring ASCII case.
+    /// This is what `str::eq_ignore_ascii_case` does through `Deref`, spelled
+    /// out for discoverability.
+    pub fn eq_ignore_ascii_case(&self, other: &str) -> bool {
+        self.0.as_bytes().eq_ignore_ascii_case(other.as_bytes())
+    }
+
+    /// Returns whether this string and `other` are equal once every character
+    /// in `ignore` has been removed from both.
+    pub fn eq_ignoring_chars(&self, other: &str, ignore: &[char]) -> bool {
+        let kept = |c: &char| !ignore.contains(c);
+        self.0.chars().filter(&kept).eq(other.chars().filter(&kept))
+    }
+
+    /// Returns the Normalization Form C (canonical composition) of this
+    /// string.
+    ///
+    /// http://www.unicode.org/reports/tr15/
+    pub fn normalize_nfc(&self) -> DOMString {
+        self.0.nfc().collect()
+    }
+
+    /// Returns a hash of the contents of this string which, unlike its `Hash`
+    /// implementation fed to the default randomly-keyed hasher, is the same
+    /// from one run to the next.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = FnvHasher::default();
+        hasher.write(self.0.as_bytes());
+        hasher.finish()
+    }
+
+    /// Serializes the UTF-8 bytes of this string with the
+    /// `application/x-www-form-urlencoded` byte serializer.
+    ///
+    /// https://url.spec.whatwg.org/#concept-urlencoded-byte-serializer
+    pub fn form_urlencode(&self) -> String {
+        const HEX_DIGITS: &'static [u8] = b"0123456789ABCDEF";
+        let mut result = String::with_capacity(self.0.len());
+        for byte in self.0.bytes() {
+            match byte {
+                b' ' => result.push('+'),
+                b'*' | b'-' | b'.' | b'_' | b'0'...b'9' | b'A'...b'Z' | b'a'...b'z' => {
+                    result.push(byte as char)
+                },
+                _ => {
+                    result.push('%');
+                    result.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+                    result.push(HEX_DIGITS[(byte & 0xf) as usize] as char);
+                },
+            }
+        }
+        result
+    }
+
+    /// Escapes this string for use within a single- or double-quoted
+    /// JavaScript string literal. Backslashes, quotes, CR and LF are escaped
+    /// with a backslash; other control characters and the U+2028 LINE
+    /// SEPARATOR and U+2029 PARAGRAPH SEPARATOR line terminators become
+    /// `\uXXXX` sequences.
+    pub fn escape_js_string(&self) -> String {
+        let mut result = String::with_capacity(self.0.len());
+        for c in self.0.chars() {
+            match c {
+                '\\' => result.push_str("\\\\"),
+                '\'' => result.push_str("\\'"),
+                '"' => result.push_str("\\\""),
+                '\n' => result.push_str("\\n"),
+                '\r' => result.push_str("\\r"),
+                '\u{2028}' | '\u{2029}' => result.push_str(&format!("\\u{:04X}", c as u32)),
+                _ if c.is_control() => result.push_str(&format!("\\u{:04X}", c as u32)),
+                _ => result.push(c),
+            }
+        }
+        result
+    }
+
+    /// Splits this string on each occurrence of `sep`, returning the pieces
+    /// as owned `DOMString`s.
+    pub fn split_to_dom_strings(&self, sep: char) -> Vec<DOMString> {
+        self.0.split(sep).map(DOMString::from).collect()
+    }
+
+    /// Returns a double-quoted representation of this string for logging,
+    /// truncated to its first `max` chars as by `truncate_with_ellipsis` and
+    /// escaped as by `escape_js_string`.
+    pub fn debug_truncated(&self, max: usize) -> String {
+        let truncated = DOMString::from(truncate_with_ellipsis(&self.0, max));
+        format!("\"{}\"", truncated.escape_js_string())
+    }
+
+    /// Returns a new `DOMString` containing this string repeated `n` times.
+    pub fn repeat(&self, n: usize) -> DOMString {
+        let mut result = String::with_capacity(self.0.len() * n);
+        for _ in 0..n {
+            result.push_str(&self.0);
+        }
+        DOMString::from_string(result)
+    }
+
+    /// Returns a new `DOMString` containing this string preceded by as many
+    /// `pad` chars as are needed to make it `width` chars long.
+    pub fn pad_start(&self, width: usize, pad: char) -> DOMString {
+        let length = self.0.chars().count();
+        let mut result = String::with_capacity(self.0.len() + width.saturating_sub(length) * pad.len_utf8());
+        for _ in length..width {
+            result.push(pad);
+        }
+        result.push_str(&self.0);
+        DOMString::from_string(result)
+    }
+
+    /// Strips leading and trailing HTML whitespace and collapses each interior
+    /// run of whitespace into a single U+0020 SPACE, in place.
+    ///
+    /// https://html.spec.whatwg.org/multipage/#strip-and-collapse-whitespace
+    pub fn normalize_whitespace(&mut self) {
+        // Only ASCII whitespace bytes are removed or replaced (by another
+        // ASCII byte), so the contents remain valid UTF-8 throughout.
+        let bytes = unsafe { self.string_mut().as_mut_vec() };
+        let mut write = 0;
+        let mut pending_space = false;
+        for read in 0..bytes.len() {
+            let byte = bytes[read];
+            if char_is_whitespace(byte as char) {
+                pending_space = write > 0;
+                continue;
+            }
+            if pending_space {
+                bytes[write] = b' ';
+                write += 1;
+                pending_space = false;
+            }
+            bytes[write] = byte;
+            write += 1;
+        }
+        bytes.truncate(write);
+    }
+
+    /// Replaces each ASCII character `c` of this string with `f(c)`, in place
+    /// where possible; non-ASCII characters are left untouched. A non-ASCII
+    /// byte returned by `f` is taken to be the Latin-1 character it encodes,
+    /// so the contents always remain valid UTF-8.
+    pub fn ascii_transform<F: FnMut(u8) -> u8>(&mut self, mut f: F) {
+        if !self.is_ascii() {
+            let transformed = self.0.chars().map(|c| {
+                if c.is_ascii() { f(c as u8) as char } else { c }
+            }).collect::<String>();
+            *self.string_mut() = transformed;
+            return;
+        }
+
+        let inner = self.string_mut();
+        let mut index = 0;
+        let mut overflow = None;
+        {
+            // Only ASCII bytes are written, so the contents remain valid UTF-8.
+            let bytes = unsafe { inner.as_mut_vec() };
+            while index < bytes.len() {
+                let byte = f(bytes[index]);
+                if !byte.is_ascii() {
+                    overflow = Some(byte);
+                    break;
+                }
+                bytes[index] = byte;
+                index += 1;
+            }
+        }
+        if let Some(byte) = overflow {
+            let tail = inner.as_bytes()[index + 1..].to_owned();
+            inner.truncate(index);
+            inner.push(byte as char);
+            inner.extend(tail.into_iter().map(|b| f(b) as char));
+        }
+    }
+
+    /// Replaces each CRLF pair and each lone CR with a single LF.
+    pub fn normalize_newlines(&mut self) {
+        if self.0.contains('\r') {
+            let normalized = normalize_dom_newlines(&self.0);
+            *self.string_mut() = normalized;
+        }
+    }
 }
 
 impl Default for DOMString {
     fn default() -> Self {
-        DOMString(String::new())
+        DOMString::new()
     }
 }
 
@@ -59,7 +420,7 @@ impl Deref for DOMString {
 impl DerefMut for DOMString {
     #[inline]
     fn deref_mut(&mut self) -> &mut str {
-        &mut self.0
+        self.string_mut()
     }
 }
 
@@ -69,6 +430,12 @@ impl AsRef<str> for DOMString {
     }
 }
 
+impl fmt::Debug for DOMString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("DOMString").field(&self.0).finish()
+    }
+}
+
 impl fmt::Display for DOMString {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -88,9 +455,15 @@ impl<'a> PartialEq<&'a str> for DOMString {
     }
 }
 
+impl PartialEq<[u8]> for DOMString {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_bytes() == other
+    }
+}
+
 impl From<String> for DOMString {
     fn from(contents: String) -> DOMString {
-        DOMString(contents)
+        DOMString::from_string(contents)
     }
 }
 
@@ -112,9 +485,30 @@ impl Into<Vec<u8>> for DOMString {
     }
 }
 
+impl Serialize for DOMString {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error> where S: Serializer {
+        self.0.serialize(serializer)
+    }
+}
+
+impl Deserialize for DOMString {
+    fn deserialize<D>(deserializer: &mut D) -> Result<DOMString, D::Error> where D: Deserializer {
+        String::deserialize(deserializer).map(DOMString::from_string)
+    }
+}
+
 impl Extend<char> for DOMString {
     fn extend<I>(&mut self, iterable: I) where I: IntoIterator<Item=char> {
-        self.0.extend(iterable)
+        self.string_mut().extend(iterable)
+    }
+}
+
+impl FromIterator<char> for DOMString {
+    fn from_iter<I>(iterable: I) -> DOMString where I: IntoIterator<Item=char> {
+        let iterator = iterable.into_iter();
+        let mut string = String::with_capacity(iterator.size_hint().0);
+        string.extend(iterator);
+        DOMString::from_string(string)
     }
 }
 
@@ -134,6 +528,71 @@ pub fn char_is_whitespace(c: char) -> bool {
     WHITESPACE.contains(&c)
 }
 
+/// Accumulates text into a `DOMString`, collapsing each run of whitespace into
+/// a single U+0020 SPACE as it is appended. Runs spanning several `push_str`
+/// calls are collapsed too. Leading and trailing whitespace is collapsed but
+/// not trimmed.
+#[derive(Clone, Debug, Default)]
+pub struct CollapsingStringBuilder {
+    string: String,
+    last_was_whitespace: bool,
+}
+
+impl CollapsingStringBuilder {
+    pub fn new() -> CollapsingStringBuilder {
+        CollapsingStringBuilder::default()
+    }
+
+    pub fn push_str(&mut self, s: &str) {
+        self.string.reserve(s.len());
+        for ch in s.chars() {
+            if char_is_whitespace(ch) {
+                if !self.last_was_whitespace {
+                    self.string.push(' ');
+                    self.last_was_whitespace = true;
+                }
+            } else {
+                self.string.push(ch);
+                self.last_was_whitespace = false;
+            }
+        }
+    }
+
+    pub fn finish(self) -> DOMString {
+        DOMString::from(self.string)
+    }
+}
+
+fn is_valid_email(address: &str) -> bool {
+    let at = match address.find('@') {
+        Some(at) => at,
+        None => return false,
+    };
+    let (local, domain) = (&address[..at], &address[at + 1..]);
+
+    // 1*( atext / "." )
+    let is_local_char = |b: u8| match b {
+        b'a'...b'z' | b'A'...b'Z' | b'0'...b'9' | b'.' | b'!' | b'#' | b'$' | b'%' | b'&' |
+        b'\'' | b'*' | b'+' | b'/' | b'=' | b'?' | b'^' | b'_' | b'`' | b'{' | b'|' | b'}' |
+        b'~' | b'-' => true,
+        _ => false,
+    };
+    if local.is_empty() || !local.bytes().all(is_local_char) {
+        return false;
+    }
+
+    // label *( "." label ), where a label has at most 63 letters, digits and
+    // hyphens, and neither starts nor ends with a hyphen.
+    domain.split('.').all(|label| {
+        label.len() >= 1 && label.len() <= 63 &&
+        !label.starts_with('-') && !label.ends_with('-') &&
+        label.bytes().all(|b| match b {
+            b'a'...b'z' | b'A'...b'Z' | b'0'...b'9' | b'-' => true,
+            _ => false,
+        })
+    })
+}
+
 /// A "space character" according to:
 ///
 /// https://html.spec.whatwg.org/multipage/#space-character
@@ -220,6 +679,166 @@ pub fn parse_unsigned_integer<T: Iterator<Item=char>>(input: T) -> Option<u32> {
     })
 }
 
+/// Parse a floating-point number according to
+/// <https://html.spec.whatwg.org/multipage/#rules-for-parsing-floating-point-number-values>.
+/// Trailing garbage is ignored.
+pub fn parse_floating_point_number(input: &str) -> Option<f64> {
+    parse_floating_point_prefix(input).map(|(value, _)| value)
+}
+
+/// Like `parse_floating_point_number`, but also returns the trailing garbage
+/// following the number.
+fn parse_floating_point_prefix(input: &str) -> Option<(f64, &str)> {
+    fn skip_digits(bytes: &[u8], mut index: usize) -> usize {
+        while index < bytes.len() && bytes[index] >= b'0' && bytes[index] <= b'9' {
+            index += 1;
+        }
+        index
+    }
+
+    // Step 3
+    let input = input.trim_left_matches(WHITESPACE);
+    let bytes = input.as_bytes();
+
+    // Step 5
+    let mut end = match bytes.first() {
+        Some(&b'-') | Some(&b'+') => 1,
+        _ => 0,
+    };
+
+    // Steps 6 to 8
+    let integer_end = skip_digits(bytes, end);
+    if integer_end == end {
+        let starts_with_fraction = bytes.len() > end + 1 && bytes[end] == b'.' &&
+                                   bytes[end + 1] >= b'0' && bytes[end + 1] <= b'9';
+        if !starts_with_fraction {
+            return None;
+        }
+    }
+    end = integer_end;
+
+    // Steps 10 & 11: a full stop which is not followed by a digit ends the
+    // number, skipping the exponent.
+    let mut has_exponent = true;
+    if end < bytes.len() && bytes[end] == b'.' {
+        let fraction_end = skip_digits(bytes, end + 1);
+        if fraction_end > end + 1 {
+            end = fraction_end;
+        } else {
+            has_exponent = false;
+        }
+    }
+    if has_exponent && end < bytes.len() && (bytes[end] == b'e' || bytes[end] == b'E') {
+        let mut exponent_start = end + 1;
+        if exponent_start < bytes.len() &&
+           (bytes[exponent_start] == b'-' || bytes[exponent_start] == b'+') {
+            exponent_start += 1;
+        }
+        let exponent_end = skip_digits(bytes, exponent_start);
+        if exponent_end > exponent_start {
+            end = exponent_end;
+        }
+    }
+
+    // Step 12: Conversion
+    match f64::from_str(&input[..end]) {
+        Ok(value) if value.is_finite() => Some((if value == 0. { 0. } else { value }, &input[end..])),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumberOrPercentage {
+    Number(f64),
+    /// A percentage, as a fraction (i.e. `50%` is `0.5`).
+    Percentage(f64),
+}
+
+/// Parses a floating-point number as per `parse_floating_point_number`, or a
+/// percentage if the number is immediately followed by `%`. As with the
+/// former, anything after the number (or the `%`) is ignored.
+pub fn parse_number_or_percentage(input: &str) -> Option<NumberOrPercentage> {
+    parse_floating_point_prefix(input).map(|(number, rest)| {
+        if rest.starts_with('%') {
+            NumberOrPercentage::Percentage(number / 100.)
+        } else {
+            NumberOrPercentage::Number(number)
+        }
+    })
+}
+
+/// Computes the aspect ratio of an image from its `width` and `height`
+/// attribute values, each parsed as a non-negative integer. Returns `None` if
+/// either is unparseable or `height` is zero.
+///
+/// https://html.spec.whatwg.org/multipage/#dimension-attributes
+pub fn parse_aspect_ratio(width: &str, height: &str) -> Option<f64> {
+    match (parse_unsigned_integer(width.chars()), parse_unsigned_integer(height.chars())) {
+        (Some(_), Some(0)) => None,
+        (Some(width), Some(height)) => Some(width as f64 / height as f64),
+        _ => None,
+    }
+}
+
+/// Parse an integer like `parse_integer`, additionally accepting single `_`
+/// separators between digits (e.g. `1_000`). A leading, trailing or doubled
+/// separator makes the input invalid.
+pub fn parse_integer_allow_separators(input: &str) -> Option<i32> {
+    let mut digits = String::new();
+    let mut chars = input.trim_left_matches(HTML_SPACE_CHARACTERS).chars().peekable();
+
+    match chars.peek() {
+        Some(&c @ '-') | Some(&c @ '+') => {
+            digits.push(c);
+            chars.next();
+        },
+        _ => (),
+    }
+
+    let mut previous_was_digit = false;
+    while let Some(&c) = chars.peek() {
+        match c {
+            '0'...'9' => {
+                digits.push(c);
+                previous_was_digit = true;
+            },
+            '_' => {
+                if !previous_was_digit {
+                    return None;
+                }
+                match chars.clone().nth(1) {
+                    Some('0'...'9') => (),
+                    _ => return None,
+                }
+                previous_was_digit = false;
+            },
+            _ => break,
+        }
+        chars.next();
+    }
+
+    parse_integer(digits.chars())
+}
+
+/// Parses exactly `width` ASCII digits as a non-negative integer, as used by
+/// the components of date and time strings (e.g. `YYYY` or `MM`). Returns
+/// `None` if `input` contains anything else, or too few or too many digits.
+pub fn parse_fixed_width_integer(input: &str, width: usize) -> Option<u32> {
+    if input.len() != width || width == 0 {
+        return None;
+    }
+    input.bytes().fold(Some(0u32), |accumulator, byte| {
+        match byte {
+            b'0'...b'9' => accumulator.and_then(|accumulator| {
+                accumulator.checked_mul(10)
+            }).and_then(|accumulator| {
+                accumulator.checked_add((byte - b'0') as u32)
+            }),
+            _ => None,
+        }
+    })
+}
+
 #[derive(Clone, Copy, Debug, HeapSizeOf, PartialEq)]
 pub enum LengthOrPercentageOrAuto {
     Auto,
@@ -227,11 +846,43 @@ pub enum LengthOrPercentageOrAuto {
     Length(Au),
 }
 
+impl LengthOrPercentageOrAuto {
+    /// Resolves this value against the length of the containing block.
+    /// Returns `None` for `Auto`.
+    pub fn resolve(&self, containing: Au) -> Option<Au> {
+        match *self {
+            LengthOrPercentageOrAuto::Auto => None,
+            LengthOrPercentageOrAuto::Percentage(percentage) => Some(containing.scale_by(percentage)),
+            LengthOrPercentageOrAuto::Length(length) => Some(length),
+        }
+    }
+}
+
 /// TODO: this function can be rewritten to return Result<LengthOrPercentage, _>
 /// Parses a dimension value per HTML5 § 2.4.4.4. If unparseable, `Auto` is
 /// returned.
 /// https://html.spec.whatwg.org/multipage/#rules-for-parsing-dimension-values
-pub fn parse_length(mut value: &str) -> LengthOrPercentageOrAuto {
+pub fn parse_length(value: &str) -> LengthOrPercentageOrAuto {
+    parse_length_with_remainder(value).0
+}
+
+/// Parses a dimension value per HTML5 § 2.4.4.4 like `parse_length`, also
+/// returning whether the number was directly followed by a `px` unit (ASCII
+/// case-insensitively).
+pub fn parse_length_tagged(value: &str) -> (LengthOrPercentageOrAuto, bool) {
+    let (length, remainder) = parse_length_with_remainder(value);
+    let has_px_unit = match length {
+        LengthOrPercentageOrAuto::Length(_) => {
+            remainder.len() >= 2 && remainder.as_bytes()[..2].eq_ignore_ascii_case(b"px")
+        },
+        _ => false,
+    };
+    (length, has_px_unit)
+}
+
+/// Shared implementation of `parse_length` and `parse_length_tagged`, which
+/// also returns the unparsed remainder of `value`.
+fn parse_length_with_remainder(mut value: &str) -> (LengthOrPercentageOrAuto, &str) {
     // Steps 1 & 2 are not relevant
 
     // Step 3
@@ -239,7 +890,7 @@ pub fn parse_length(mut value: &str) -> LengthOrPercentageOrAuto {
 
     // Step 4
     if value.is_empty() {
-        return LengthOrPercentageOrAuto::Auto
+        return (LengthOrPercentageOrAuto::Auto, value)
     }
 
     // Step 5
@@ -250,7 +901,7 @@ pub fn parse_length(mut value: &str) -> LengthOrPercentageOrAuto {
     // Steps 6 & 7
     match value.chars().nth(0) {
         Some('0'...'9') => {},
-        _ => return LengthOrPercentageOrAuto::Auto,
+        _ => return (LengthOrPercentageOrAuto::Auto, value),
     }
 
     // Steps 8 to 13
@@ -280,19 +931,20 @@ pub fn parse_length(mut value: &str) -> LengthOrPercentageOrAuto {
             }
         }
     }
+    let remainder = &value[end_index..];
     value = &value[..end_index];
 
     if found_percent {
         let result: Result<f32, _> = FromStr::from_str(value);
         match result {
-            Ok(number) => return LengthOrPercentageOrAuto::Percentage((number as f32) / 100.0),
-            Err(_) => return LengthOrPercentageOrAuto::Auto,
+            Ok(number) => return (LengthOrPercentageOrAuto::Percentage((number as f32) / 100.0), remainder),
+            Err(_) => return (LengthOrPercentageOrAuto::Auto, remainder),
         }
     }
 
     match FromStr::from_str(value) {
-        Ok(number) => LengthOrPercentageOrAuto::Length(Au::from_f64_px(number)),
-        Err(_) => LengthOrPercentageOrAuto::Auto,
+        Ok(number) => (LengthOrPercentageOrAuto::Length(Au::from_f64_px(number)), remainder),
+        Err(_) => (LengthOrPercentageOrAuto::Auto, remainder),
     }
 }
 
@@ -307,6 +959,67 @@ pub fn parse_nonzero_length(value: &str) -> LengthOrPercentageOrAuto {
     }
 }
 
+#[derive(Clone, Copy, Debug, HeapSizeOf, PartialEq)]
+pub enum MultiLength {
+    Absolute(Au),
+    /// A percentage, as a fraction (i.e. `25%` is `0.25`).
+    Percentage(f32),
+    /// A relative length, e.g. `2*`. A bare `*` has a value of zero.
+    Relative(f32),
+}
+
+/// Parses a list of dimensions, as used by the `cols` and `rows` attributes of
+/// `<frameset>`. Whitespace around the commas is ignored.
+///
+/// https://html.spec.whatwg.org/multipage/#rules-for-parsing-a-list-of-dimensions
+pub fn parse_multi_length_list(input: &str) -> Vec<MultiLength> {
+    // Steps 1 & 2
+    let input = if input.ends_with(',') { &input[..input.len() - 1] } else { input };
+
+    // Steps 3 to 6
+    input.split(',').map(|token| {
+        let mut chars = token.trim_matches(WHITESPACE).chars().peekable();
+
+        let mut value = 0f64;
+        if chars.peek().is_none() {
+            return MultiLength::Relative(0.);
+        }
+
+        // The integer part is accumulated as a float, so that an over-long
+        // one saturates rather than overflowing.
+        while let Some(digit) = chars.peek().and_then(|ch| ch.to_digit(10)) {
+            value = value * 10. + digit as f64;
+            chars.next();
+        }
+
+        if chars.peek() == Some(&'.') {
+            chars.next();
+            let mut divisor = 1f64;
+            let mut fraction = 0f64;
+            while let Some(&ch) = chars.peek() {
+                if let Some(digit) = ch.to_digit(10) {
+                    divisor *= 10.;
+                    fraction += digit as f64 / divisor;
+                } else if !char_is_whitespace(ch) {
+                    break
+                }
+                chars.next();
+            }
+            value += fraction;
+        }
+
+        while chars.peek().map_or(false, |&ch| char_is_whitespace(ch)) {
+            chars.next();
+        }
+
+        match chars.peek() {
+            Some(&'%') => MultiLength::Percentage((value / 100.) as f32),
+            Some(&'*') => MultiLength::Relative(value as f32),
+            _ => MultiLength::Absolute(Au::from_f64_px(value)),
+        }
+    }).collect()
+}
+
 /// https://html.spec.whatwg.org/multipage/#rules-for-parsing-a-legacy-font-size
 pub fn parse_legacy_font_size(mut input: &str) -> Option<&'static str> {
     // Steps 1 & 2 are not relevant
@@ -491,47 +1204,421 @@ pub fn parse_legacy_color(mut input: &str) -> Result<RGBA, ()> {
 }
 
 
-#[derive(Clone, Eq, PartialEq, Hash, Debug, Deserialize, Serialize)]
-pub struct LowercaseString {
-    inner: String,
+/// Parses a CSS color value. Returns `Err` for `currentColor`, which cannot
+/// be resolved without a context, and for anything that is not a color.
+pub fn parse_color(input: &str) -> Result<RGBA, ()> {
+    let mut parser = cssparser::Parser::new(input);
+    match Color::parse(&mut parser) {
+        Ok(Color::RGBA(rgba)) => {
+            if parser.is_exhausted() {
+                Ok(rgba)
+            } else {
+                Err(())
+            }
+        },
+        _ => Err(()),
+    }
 }
 
-impl LowercaseString {
-    pub fn new(s: &str) -> LowercaseString {
-        LowercaseString {
-            inner: s.to_lowercase(),
+/// Parses a CSS color value like `parse_color`, resolving `currentColor` to
+/// `current_color`.
+pub fn resolve_color(input: &str, current_color: RGBA) -> Option<RGBA> {
+    let input = input.trim_matches(WHITESPACE);
+    if input.eq_ignore_ascii_case("currentColor") {
+        return Some(current_color);
+    }
+    if input.eq_ignore_ascii_case("transparent") {
+        return Some(RGBA { red: 0., green: 0., blue: 0., alpha: 0. });
+    }
+    parse_color(input).ok()
+}
+
+/// Parses a single channel of an `rgb()` color, either an integer (e.g.
+/// `255`) or a percentage (e.g. `50%`), into a byte. Out-of-range values are
+/// clamped.
+pub fn parse_color_channel(token: &str) -> Option<u8> {
+    fn is_numeric(s: &str, allow_fraction: bool) -> bool {
+        let digits = s.trim_left_matches(|c: char| c == '+' || c == '-');
+        if s.len() - digits.len() > 1 {
+            return false;
+        }
+        let mut has_digit = false;
+        let mut has_full_stop = false;
+        for b in digits.bytes() {
+            match b {
+                b'0'...b'9' => has_digit = true,
+                b'.' if allow_fraction && !has_full_stop => has_full_stop = true,
+                _ => return false,
+            }
         }
+        has_digit
     }
+
+    let (value, maximum) = if token.ends_with('%') {
+        let number = &token[..token.len() - 1];
+        if !is_numeric(number, true) {
+            return None;
+        }
+        (number, 100.)
+    } else {
+        if !is_numeric(token, false) {
+            return None;
+        }
+        (token, 255.)
+    };
+
+    f64::from_str(value).ok().map(|value| {
+        (value * 255. / maximum).round().max(0.).min(255.) as u8
+    })
 }
 
-impl Deref for LowercaseString {
-    type Target = str;
+/// Parses a `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa` color. Unlike
+/// `parse_legacy_color`, no error recovery is performed: anything else
+/// returns `None`.
+pub fn parse_hex_color(input: &str) -> Option<RGBA> {
+    if !input.starts_with('#') {
+        return None;
+    }
 
-    #[inline]
-    fn deref(&self) -> &str {
-        &*self.inner
+    let mut digits = Vec::with_capacity(8);
+    for ch in input[1..].chars() {
+        match ch.to_digit(16) {
+            Some(digit) => digits.push(digit as u8),
+            None => return None,
+        }
     }
-}
 
-/// Creates a String from the given null-terminated buffer.
-/// Panics if the buffer does not contain UTF-8.
-pub unsafe fn c_str_to_string(s: *const c_char) -> String {
-    from_utf8(CStr::from_ptr(s).to_bytes()).unwrap().to_owned()
-}
+    let channels: Vec<u8> = match digits.len() {
+        3 | 4 => digits.iter().map(|&digit| digit * 17).collect(),
+        6 | 8 => digits.chunks(2).map(|pair| (pair[0] << 4) | pair[1]).collect(),
+        _ => return None,
+    };
 
-pub fn str_join<I, T>(strs: I, join: &str) -> String
-    where I: IntoIterator<Item=T>, T: AsRef<str>,
-{
-    strs.into_iter().enumerate().fold(String::new(), |mut acc, (i, s)| {
-        if i > 0 { acc.push_str(join); }
-        acc.push_str(s.as_ref());
-        acc
+    Some(RGBA {
+        red: channels[0] as f32 / 255.0,
+        green: channels[1] as f32 / 255.0,
+        blue: channels[2] as f32 / 255.0,
+        alpha: channels.get(3).map_or(1.0, |&alpha| alpha as f32 / 255.0),
     })
 }
 
-// Lifted from Rust's StrExt implementation, which is being removed.
-pub fn slice_chars(s: &str, begin: usize, end: usize) -> &str {
-    assert!(begin <= end);
+/// Returns whether `s` is a valid simple color: a `#` followed by exactly six
+/// hex digits of either case.
+///
+/// https://html.spec.whatwg.org/multipage/#valid-simple-colour
+pub fn is_valid_simple_color(s: &str) -> bool {
+    s.len() == 7 && s.starts_with('#') && s[1..].chars().all(|c| c.is_digit(16))
+}
+
+/// Sanitizes the value of an `<input type=color>`: a valid simple color is
+/// lowercased, and anything else becomes `#000000`.
+///
+/// https://html.spec.whatwg.org/multipage/#color-state-(type=color):value-sanitization-algorithm
+pub fn sanitize_simple_color(s: &str) -> String {
+    if is_valid_simple_color(s) {
+        s.to_ascii_lowercase()
+    } else {
+        "#000000".to_owned()
+    }
+}
+
+/// Packs `color` into a `0xAARRGGBB` integer, rounding each channel to the
+/// nearest byte. Out-of-range channels are clamped.
+pub fn rgba_to_u32(color: &RGBA) -> u32 {
+    fn to_byte(channel: f32) -> u32 {
+        (channel.max(0.).min(1.) * 255.).round() as u32
+    }
+    to_byte(color.alpha) << 24 | to_byte(color.red) << 16 | to_byte(color.green) << 8 |
+        to_byte(color.blue)
+}
+
+/// Unpacks a `0xAARRGGBB` integer into an `RGBA` color.
+pub fn rgba_from_u32(packed: u32) -> RGBA {
+    fn from_byte(packed: u32, shift: u32) -> f32 {
+        ((packed >> shift) & 0xff) as f32 / 255.
+    }
+    RGBA {
+        red: from_byte(packed, 16),
+        green: from_byte(packed, 8),
+        blue: from_byte(packed, 0),
+        alpha: from_byte(packed, 24),
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Deserialize, Serialize)]
+pub struct LowercaseString {
+    inner: String,
+}
+
+impl LowercaseString {
+    pub fn new(s: &str) -> LowercaseString {
+        LowercaseString {
+            inner: s.to_lowercase(),
+        }
+    }
+
+    /// Returns whether `needle` occurs in this string. `needle` must already
+    /// be lowercase for the check to be case-insensitive.
+    pub fn contains(&self, needle: &str) -> bool {
+        self.inner.contains(needle)
+    }
+}
+
+impl Deref for LowercaseString {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        &*self.inner
+    }
+}
+
+impl fmt::Display for LowercaseString {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&*self.inner, f)
+    }
+}
+
+/// Compares an element's `id` attribute value against a requested ID, e.g. a
+/// fragment identifier. IDs are matched case-sensitively, except in quirks
+/// mode where they are matched ASCII case-insensitively.
+///
+/// https://html.spec.whatwg.org/multipage/#case-sensitivity-of-selectors
+pub fn id_matches(attr_value: &str, requested: &str, quirks: bool) -> bool {
+    if quirks {
+        attr_value.eq_ignore_ascii_case(requested)
+    } else {
+        attr_value == requested
+    }
+}
+
+/// Splits a MIME type into its lowercased `type/subtype` essence and the
+/// remaining parameter string following the first `;`. No validation of the
+/// essence is performed.
+///
+/// https://mimesniff.spec.whatwg.org/#mime-type-essence
+pub fn split_mime_essence(input: &str) -> (LowercaseString, &str) {
+    let (essence, parameters) = match input.find(';') {
+        Some(index) => (&input[..index], &input[index + 1..]),
+        None => (input, ""),
+    };
+    (LowercaseString::new(essence.trim_matches(WHITESPACE)), parameters)
+}
+
+/// Returns the lowercased `type/subtype` essence of a MIME type, or `None` if
+/// either the type or the subtype is not a valid token. Parameters are
+/// ignored.
+pub fn canonicalize_mime_essence(input: &str) -> Option<LowercaseString> {
+    let (essence, _) = split_mime_essence(input);
+    let valid = match split_once_char(&essence, '/') {
+        Some((type_, subtype)) => is_token_str(type_) && is_token_str(subtype),
+        None => false,
+    };
+    if valid {
+        Some(essence)
+    } else {
+        None
+    }
+}
+
+/// Feeds the ASCII-lowercased bytes of `s` to `state`, without allocating an
+/// intermediate lowercased string.
+pub fn hash_ascii_lowercase<H: Hasher>(s: &str, state: &mut H) {
+    for byte in s.bytes() {
+        state.write_u8(byte.to_ascii_lowercase());
+    }
+    // Mirror `str`'s `Hash` implementation, so that the hash is prefix-free.
+    state.write_u8(0xff);
+}
+
+/// A string slice which hashes and compares ASCII case-insensitively, for use
+/// as a lookup key in maps of attribute names and the like.
+#[derive(Clone, Copy, Debug)]
+pub struct AsciiCaseInsensitive<'a>(pub &'a str);
+
+impl<'a> Hash for AsciiCaseInsensitive<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_ascii_lowercase(self.0, state)
+    }
+}
+
+impl<'a> PartialEq for AsciiCaseInsensitive<'a> {
+    fn eq(&self, other: &AsciiCaseInsensitive<'a>) -> bool {
+        self.0.eq_ignore_ascii_case(other.0)
+    }
+}
+
+impl<'a> Eq for AsciiCaseInsensitive<'a> {}
+
+/// Creates a String from the given null-terminated buffer.
+/// Panics if the buffer does not contain UTF-8.
+pub unsafe fn c_str_to_string(s: *const c_char) -> String {
+    from_utf8(CStr::from_ptr(s).to_bytes()).unwrap().to_owned()
+}
+
+pub fn str_join<I, T>(strs: I, join: &str) -> String
+    where I: IntoIterator<Item=T>, T: AsRef<str>,
+{
+    strs.into_iter().enumerate().fold(String::new(), |mut acc, (i, s)| {
+        if i > 0 { acc.push_str(join); }
+        acc.push_str(s.as_ref());
+        acc
+    })
+}
+
+/// Joins `parts` with `sep` like `str_join`, but trims any occurrences of `sep`
+/// on either side of each junction, so that the parts are always separated
+/// by exactly one `sep`.
+pub fn join_no_dup_sep(parts: &[&str], sep: char) -> String {
+    let mut result = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            result.push_str(part);
+            continue;
+        }
+        let length = result.trim_right_matches(sep).len();
+        result.truncate(length);
+        result.push(sep);
+        result.push_str(part.trim_left_matches(sep));
+    }
+    result
+}
+
+/// Strips all leading and trailing occurrences of any of `chars` from `s`.
+pub fn trim_chars<'a>(s: &'a str, chars: &[char]) -> &'a str {
+    s.trim_matches(chars)
+}
+
+/// Splits `s` at the first occurrence of `sep`, returning the parts before and
+/// after it, or `None` if `sep` does not occur.
+pub fn split_once_char(s: &str, sep: char) -> Option<(&str, &str)> {
+    s.find(sep).map(|index| (&s[..index], &s[index + sep.len_utf8()..]))
+}
+
+/// Splits `s` at the last occurrence of `sep`, returning the parts before and
+/// after it, or `None` if `sep` does not occur.
+pub fn rsplit_once_char(s: &str, sep: char) -> Option<(&str, &str)> {
+    s.rfind(sep).map(|index| (&s[..index], &s[index + sep.len_utf8()..]))
+}
+
+/// Splits `s` into its first run of non-whitespace characters and the rest,
+/// ignoring leading whitespace in both.
+pub fn split_first_whitespace(s: &str) -> (&str, &str) {
+    let s = s.trim_left_matches(WHITESPACE);
+    let end = s.find(WHITESPACE).unwrap_or(s.len());
+    (&s[..end], s[end..].trim_left_matches(WHITESPACE))
+}
+
+/// An iterator over the chars of a string and their offsets in UTF-16 code
+/// units, as returned by `char_indices_utf16`.
+#[derive(Clone)]
+pub struct CharIndicesUtf16<'a> {
+    chars: Chars<'a>,
+    offset: usize,
+}
+
+impl<'a> Iterator for CharIndicesUtf16<'a> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        self.chars.next().map(|c| {
+            let offset = self.offset;
+            self.offset += if c > '\u{ffff}' { 2 } else { 1 };
+            (offset, c)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chars.size_hint()
+    }
+}
+
+/// Returns an iterator over the chars of `s` and their offsets in UTF-16 code
+/// units, as used by script, e.g. in `Range` and `Selection`.
+pub fn char_indices_utf16(s: &str) -> CharIndicesUtf16 {
+    CharIndicesUtf16 {
+        chars: s.chars(),
+        offset: 0,
+    }
+}
+
+/// Returns the length of `s` in UTF-16 code units, without decoding it.
+pub fn utf16_len(s: &str) -> usize {
+    // Every char is one code unit, except those outside the BMP (encoded in
+    // UTF-8 with a four-byte sequence starting with 0xF0 or above) which
+    // take two.
+    s.bytes().fold(0, |length, byte| {
+        match byte {
+            0x80...0xBF => length,
+            0xF0...0xFF => length + 2,
+            _ => length + 1,
+        }
+    })
+}
+
+/// Replaces each tab in `s` with spaces up to the next multiple of
+/// `tab_width` columns, as when rendering `text/plain` documents. Columns are
+/// counted in chars and reset after each newline. A `tab_width` of zero
+/// removes tabs.
+pub fn expand_tabs(s: &str, tab_width: usize) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut column = 0;
+    for ch in s.chars() {
+        match ch {
+            '\t' => {
+                if tab_width == 0 {
+                    continue;
+                }
+                let spaces = tab_width - column % tab_width;
+                for _ in 0..spaces {
+                    result.push(' ');
+                }
+                column += spaces;
+            },
+            '\n' => {
+                result.push(ch);
+                column = 0;
+            },
+            _ => {
+                result.push(ch);
+                column += 1;
+            },
+        }
+    }
+    result
+}
+
+/// Replaces each CRLF pair and each lone CR in `input` with a single LF, as
+/// the HTML parser does before inserting text into the DOM.
+///
+/// https://html.spec.whatwg.org/multipage/#preprocessing-the-input-stream
+pub fn normalize_dom_newlines(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '\r' {
+            if chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            result.push('\n');
+        } else {
+            result.push(ch);
+        }
+    }
+    result
+}
+
+/// Returns the byte offsets of the chars at indices `begin` and `end` of `s`,
+/// or `None` for an index beyond the end of the string.
+fn char_range_to_byte_range(s: &str, begin: usize, end: usize) -> (Option<usize>, Option<usize>) {
+    // If everything up to `end` is ASCII, char indices are byte indices. This
+    // check is still linear in `end`, but it scans bytes rather than decoding
+    // chars.
+    if end <= s.len() && s.as_bytes()[..end].is_ascii() {
+        return (Some(begin), Some(end));
+    }
+
     let mut count = 0;
     let mut begin_byte = None;
     let mut end_byte = None;
@@ -546,13 +1633,46 @@ pub fn slice_chars(s: &str, begin: usize, end: usize) -> &str {
     if begin_byte.is_none() && count == begin { begin_byte = Some(s.len()) }
     if end_byte.is_none() && count == end { end_byte = Some(s.len()) }
 
-    match (begin_byte, end_byte) {
+    (begin_byte, end_byte)
+}
+
+// Lifted from Rust's StrExt implementation, which is being removed.
+pub fn slice_chars(s: &str, begin: usize, end: usize) -> &str {
+    assert!(begin <= end);
+    match char_range_to_byte_range(s, begin, end) {
         (None, _) => panic!("slice_chars: `begin` is beyond end of string"),
         (_, None) => panic!("slice_chars: `end` is beyond end of string"),
         (Some(a), Some(b)) => unsafe { s.slice_unchecked(a, b) }
     }
 }
 
+/// Like `slice_chars`, but returns `None` instead of panicking if `begin`
+/// is greater than `end`, or either is beyond the end of the string.
+pub fn slice_chars_checked(s: &str, begin: usize, end: usize) -> Option<&str> {
+    if begin > end {
+        return None;
+    }
+    match char_range_to_byte_range(s, begin, end) {
+        (Some(a), Some(b)) => Some(unsafe { s.slice_unchecked(a, b) }),
+        _ => None,
+    }
+}
+
+/// Returns `s` if it is at most `max_chars` chars long, or otherwise its
+/// first `max_chars - 1` chars followed by an ellipsis, for a total of
+/// `max_chars` chars.
+pub fn truncate_with_ellipsis(s: &str, max_chars: usize) -> String {
+    if s.chars().nth(max_chars).is_none() {
+        return s.to_owned();
+    }
+    if max_chars == 0 {
+        return String::new();
+    }
+    let mut result = slice_chars(s, 0, max_chars - 1).to_owned();
+    result.push('\u{2026}');
+    result
+}
+
 // searches a character index in CharIndices
 // returns indices.count if not found
 pub fn search_index(index: usize, indices: CharIndices) -> isize {
@@ -599,3 +1719,1802 @@ pub fn is_token(s: &[u8]) -> bool {
         }
     })
 }
+
+/// Like `is_token`, but for a `&str`.
+pub fn is_token_str(s: &str) -> bool {
+    is_token(s.as_bytes())
+}
+
+/// Returns the bytes of `s` if it is entirely ASCII, so that they can be
+/// processed one at a time without regard for multi-byte sequences.
+pub fn as_ascii(s: &str) -> Option<&[u8]> {
+    if s.is_ascii() {
+        Some(s.as_bytes())
+    } else {
+        None
+    }
+}
+
+/// Returns whether `s` contains no ASCII uppercase letters, i.e. whether
+/// ASCII-lowercasing it would leave it unchanged.
+pub fn is_ascii_lowercase(s: &str) -> bool {
+    !s.bytes().any(|b| b >= b'A' && b <= b'Z')
+}
+
+/// Returns the rest of `s` after `prefix`, if `s` starts with `prefix` ASCII
+/// case-insensitively.
+pub fn strip_prefix_ignore_ascii_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    if s.len() >= prefix.len() && s.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes()) {
+        // Non-ASCII bytes only match themselves, so this is a char boundary.
+        Some(&s[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+/// Serializes a list of tokens as the value of a multi-valued HTTP header such
+/// as `Vary` or `Allow`. If any item is not a valid token, `Err` is returned.
+pub fn serialize_header_list<I: IntoIterator<Item=String>>(items: I) -> Result<String, ()> {
+    let mut result = String::new();
+    for (i, item) in items.into_iter().enumerate() {
+        if !is_token_str(&item) {
+            return Err(());
+        }
+        if i > 0 {
+            result.push_str(", ");
+        }
+        result.push_str(&item);
+    }
+    Ok(result)
+}
+
+/// Splits the value of a multi-valued HTTP header such as `Accept` or
+/// `Cache-Control` into its list items, trimming whitespace from each. Commas
+/// within a `quoted-string` (which may contain `\"` escapes) do not separate
+/// items, and quoted strings are returned as written. Empty items are
+/// dropped, as [RFC 7230](https://tools.ietf.org/html/rfc7230#section-7)
+/// requires.
+pub fn split_header_list(value: &str) -> Vec<String> {
+    let mut items = vec![];
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (index, byte) in value.bytes().enumerate() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match byte {
+            b'\\' if in_quotes => escaped = true,
+            b'"' => in_quotes = !in_quotes,
+            b',' if !in_quotes => {
+                items.push(&value[start..index]);
+                start = index + 1;
+            },
+            _ => {},
+        }
+    }
+    items.push(&value[start..]);
+
+    items.into_iter()
+         .map(|item| item.trim_matches(WHITESPACE))
+         .filter(|item| !item.is_empty())
+         .map(ToOwned::to_owned)
+         .collect()
+}
+
+/// Parses the value of an HTTP `Range` header per
+/// [RFC 7233](https://tools.ietf.org/html/rfc7233#section-2.1), e.g.
+/// `bytes=0-499,500-`. Each range is returned as `(first, last)`; a suffix
+/// range such as `-500` is `(None, Some(500))`. Returns `None` if the unit is
+/// not `bytes` or the syntax is invalid.
+pub fn parse_byte_ranges(value: &str) -> Option<Vec<(Option<u64>, Option<u64>)>> {
+    let value = value.trim_matches(WHITESPACE);
+    let index = match value.find('=') {
+        Some(index) => index,
+        None => return None,
+    };
+    if !value[..index].trim_matches(WHITESPACE).eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+
+    fn parse_position(position: &str) -> Result<Option<u64>, ()> {
+        if position.is_empty() {
+            return Ok(None);
+        }
+        if !position.bytes().all(|b| b >= b'0' && b <= b'9') {
+            return Err(());
+        }
+        u64::from_str(position).map(Some).map_err(|_| ())
+    }
+
+    let mut ranges = vec![];
+    for range in value[index + 1..].split(',') {
+        let range = range.trim_matches(WHITESPACE);
+        // Empty list elements are allowed by the `#rule` list syntax.
+        if range.is_empty() {
+            continue;
+        }
+        let dash = match range.find('-') {
+            Some(dash) => dash,
+            None => return None,
+        };
+        let first = match parse_position(&range[..dash]) {
+            Ok(first) => first,
+            Err(()) => return None,
+        };
+        let last = match parse_position(&range[dash + 1..]) {
+            Ok(last) => last,
+            Err(()) => return None,
+        };
+        match (first, last) {
+            (None, None) => return None,
+            (Some(first), Some(last)) if first > last => return None,
+            _ => (),
+        }
+        ranges.push((first, last));
+    }
+
+    if ranges.is_empty() {
+        None
+    } else {
+        Some(ranges)
+    }
+}
+
+/// Parses a `quoted-string` per
+/// [RFC 7230](https://tools.ietf.org/html/rfc7230#section-3.2.6) from the
+/// front of `input`, which must start with `"`. Backslash escapes (`\"`,
+/// `\\`, ...) are decoded. Returns the decoded content and the remainder of
+/// `input` after the closing quote, or `None` if the string is unterminated.
+pub fn parse_quoted_string(input: &str) -> Option<(String, &str)> {
+    if !input.starts_with('"') {
+        return None;
+    }
+
+    let mut result = String::new();
+    let mut chars = input.char_indices().skip(1);
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            '"' => return Some((result, &input[index + 1..])),
+            '\\' => match chars.next() {
+                Some((_, escaped)) => result.push(escaped),
+                None => return None,
+            },
+            _ => result.push(ch),
+        }
+    }
+    None
+}
+
+/// Returns whether the value of an `X-Content-Type-Options` header contains
+/// the `nosniff` directive, ASCII case-insensitively.
+///
+/// https://fetch.spec.whatwg.org/#should-response-to-request-be-blocked-due-to-nosniff?
+pub fn header_is_nosniff(value: &str) -> bool {
+    split_header_list(value).iter().any(|item| item.eq_ignore_ascii_case("nosniff"))
+}
+
+/// Parses the value of a header whose value is a comma-separated list of
+/// quoted strings, such as `Clear-Site-Data: "cache", "cookies"`, returning
+/// the unquoted items. Items which are not a single `quoted-string` are
+/// ignored.
+pub fn parse_quoted_directive_list(value: &str) -> Vec<String> {
+    split_header_list(value).iter().filter_map(|item| {
+        match parse_quoted_string(item) {
+            Some((directive, "")) => Some(directive),
+            _ => None,
+        }
+    }).collect()
+}
+
+/// Parses the value of a `Cache-Control` header per
+/// [RFC 7234](https://tools.ietf.org/html/rfc7234#section-5.2) into its
+/// directives, e.g. `max-age=600, no-cache`. Each directive name is
+/// lowercased, and its argument, if any, is unquoted.
+pub fn parse_cache_control(value: &str) -> Vec<(LowercaseString, Option<String>)> {
+    split_header_list(value).iter().filter_map(|directive| {
+        let (name, argument) = match split_once_char(directive, '=') {
+            Some((name, argument)) => (name, Some(argument.trim_matches(WHITESPACE))),
+            None => (&**directive, None),
+        };
+        let name = name.trim_matches(WHITESPACE);
+        if name.is_empty() {
+            return None;
+        }
+        let argument = argument.map(|argument| {
+            match parse_quoted_string(argument) {
+                Some((unquoted, "")) => unquoted,
+                _ => argument.to_owned(),
+            }
+        });
+        Some((LowercaseString::new(name), argument))
+    }).collect()
+}
+
+/// Returns the filename given by the value of a `Content-Disposition` header
+/// per [RFC 6266](https://tools.ietf.org/html/rfc6266#section-4.1). The
+/// `filename*` parameter, an
+/// [RFC 5987](https://tools.ietf.org/html/rfc5987#section-3.2) extended value
+/// in UTF-8 or ISO-8859-1, is preferred over `filename`, which may be a token
+/// or a quoted string. Returns `None` if neither is present and valid.
+pub fn content_disposition_filename(value: &str) -> Option<String> {
+    fn decode_ext_value(value: &str) -> Option<String> {
+        let mut parts = value.splitn(3, '\'');
+        let (charset, encoded) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(charset), Some(_language), Some(encoded)) => (charset, encoded),
+            _ => return None,
+        };
+        let bytes = percent_decode(encoded.as_bytes());
+        if charset.eq_ignore_ascii_case("utf-8") {
+            String::from_utf8(bytes).ok()
+        } else if charset.eq_ignore_ascii_case("iso-8859-1") {
+            Some(bytes.into_iter().map(|byte| byte as char).collect())
+        } else {
+            None
+        }
+    }
+
+    let (disposition_type, mut rest) = match value.find(';') {
+        Some(index) => (&value[..index], &value[index..]),
+        None => (value, ""),
+    };
+    if !is_token_str(disposition_type.trim_matches(WHITESPACE)) {
+        return None;
+    }
+
+    let mut filename = None;
+    let mut extended_filename = None;
+    loop {
+        rest = rest.trim_left_matches(WHITESPACE);
+        if !rest.starts_with(';') {
+            break;
+        }
+        rest = rest[1..].trim_left_matches(WHITESPACE);
+
+        let name_end = rest.find(|c: char| c == '=' || c == ';').unwrap_or(rest.len());
+        let name = rest[..name_end].trim_matches(WHITESPACE);
+        rest = &rest[name_end..];
+        if !rest.starts_with('=') {
+            continue;
+        }
+        rest = rest[1..].trim_left_matches(WHITESPACE);
+
+        let parameter_value = if rest.starts_with('"') {
+            match parse_quoted_string(rest) {
+                Some((parameter_value, remainder)) => {
+                    rest = remainder;
+                    parameter_value
+                },
+                None => return None,
+            }
+        } else {
+            let value_end = rest.find(';').unwrap_or(rest.len());
+            let parameter_value = rest[..value_end].trim_matches(WHITESPACE).to_owned();
+            rest = &rest[value_end..];
+            parameter_value
+        };
+
+        if name.eq_ignore_ascii_case("filename*") {
+            extended_filename = decode_ext_value(&parameter_value);
+        } else if name.eq_ignore_ascii_case("filename") {
+            filename = Some(parameter_value);
+        }
+    }
+    extended_filename.or(filename).and_then(|filename| {
+        if filename.is_empty() { None } else { Some(filename) }
+    })
+}
+
+/// Returns whether `s` is a well-formed language tag per the ABNF of
+/// [BCP 47](https://tools.ietf.org/html/bcp47#section-2.1). Subtags are not
+/// checked against the registry. Irregular grandfathered tags such as
+/// `i-klingon` are well-formed by this definition.
+pub fn is_well_formed_language_tag(s: &str) -> bool {
+    static IRREGULAR_GRANDFATHERED: StaticStringVec = &[
+        "en-GB-oed", "i-ami", "i-bnn", "i-default", "i-enochian", "i-hak", "i-klingon", "i-lux",
+        "i-mingo", "i-navajo", "i-pwn", "i-tao", "i-tay", "i-tsu", "sgn-BE-FR", "sgn-BE-NL",
+        "sgn-CH-DE",
+    ];
+
+    if IRREGULAR_GRANDFATHERED.iter().any(|tag| tag.eq_ignore_ascii_case(s)) {
+        return true;
+    }
+
+    fn is_alpha(subtag: &str) -> bool {
+        subtag.bytes().all(|b| match b { b'a'...b'z' | b'A'...b'Z' => true, _ => false })
+    }
+    fn is_digit(subtag: &str) -> bool {
+        subtag.bytes().all(|b| match b { b'0'...b'9' => true, _ => false })
+    }
+    fn is_alphanum(subtag: &str) -> bool {
+        subtag.bytes().all(|b| match b { b'a'...b'z' | b'A'...b'Z' | b'0'...b'9' => true, _ => false })
+    }
+
+    let subtags: Vec<&str> = s.split('-').collect();
+    if subtags.iter().any(|subtag| subtag.is_empty() || subtag.len() > 8 || !is_alphanum(subtag)) {
+        return false;
+    }
+
+    // Returns whether `subtags` is a well-formed `privateuse` sequence, i.e.
+    // "x" followed by at least one subtag.
+    fn is_private_use(subtags: &[&str]) -> bool {
+        subtags.len() > 1 && subtags[0].eq_ignore_ascii_case("x")
+    }
+
+    if is_private_use(&subtags) {
+        return true;
+    }
+
+    // language
+    let language = subtags[0];
+    if language.len() < 2 || !is_alpha(language) {
+        return false;
+    }
+    let mut i = 1;
+
+    // extlang
+    if language.len() <= 3 {
+        let mut extlangs = 0;
+        while i < subtags.len() && extlangs < 3 && subtags[i].len() == 3 && is_alpha(subtags[i]) {
+            i += 1;
+            extlangs += 1;
+        }
+    }
+
+    // script
+    if i < subtags.len() && subtags[i].len() == 4 && is_alpha(subtags[i]) {
+        i += 1;
+    }
+
+    // region
+    if i < subtags.len() && ((subtags[i].len() == 2 && is_alpha(subtags[i])) ||
+                             (subtags[i].len() == 3 && is_digit(subtags[i]))) {
+        i += 1;
+    }
+
+    // variants
+    while i < subtags.len() {
+        let subtag = subtags[i];
+        let is_variant = subtag.len() >= 5 ||
+                         (subtag.len() == 4 && is_digit(&subtag[..1]));
+        if !is_variant {
+            break;
+        }
+        i += 1;
+    }
+
+    // extensions
+    while i < subtags.len() && subtags[i].len() == 1 && !subtags[i].eq_ignore_ascii_case("x") {
+        i += 1;
+        let start = i;
+        while i < subtags.len() && subtags[i].len() >= 2 {
+            i += 1;
+        }
+        if i == start {
+            return false;
+        }
+    }
+
+    // privateuse
+    i == subtags.len() || is_private_use(&subtags[i..])
+}
+
+fn escape_html(s: &str, attribute_mode: bool) -> String {
+    let mut result = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' if attribute_mode => result.push_str("&quot;"),
+            '\u{a0}' => result.push_str("&nbsp;"),
+            _ => result.push(ch),
+        }
+    }
+    result
+}
+
+/// Escapes `&`, `<`, `>` and U+00A0 NO-BREAK SPACE for serialization as HTML
+/// text content.
+///
+/// https://html.spec.whatwg.org/multipage/#escapingString
+pub fn escape_html_text(s: &str) -> String {
+    escape_html(s, false)
+}
+
+/// Escapes `&`, `<`, `>`, `"` and U+00A0 NO-BREAK SPACE for serialization as
+/// an HTML attribute value.
+///
+/// https://html.spec.whatwg.org/multipage/#escapingString
+pub fn escape_html_attribute(s: &str) -> String {
+    escape_html(s, true)
+}
+
+/// Extracts the encoding label from the `content` attribute of a
+/// `<meta http-equiv=content-type>` element, e.g. `UTF-8` from
+/// `text/html; charset=UTF-8`. The label is returned as written; it is not
+/// resolved to an encoding.
+///
+/// https://html.spec.whatwg.org/multipage/#algorithm-for-extracting-a-character-encoding-from-a-meta-element
+pub fn extract_charset_label(content: &str) -> Option<String> {
+    // ASCII lowercasing preserves byte offsets, so indices into `lowercase`
+    // are valid in `content`.
+    let lowercase = content.to_ascii_lowercase();
+
+    // Step 1
+    let mut position = 0;
+
+    loop {
+        // Step 2
+        let mut rest = match lowercase[position..].find("charset") {
+            Some(index) => &content[position + index + "charset".len()..],
+            None => return None,
+        };
+
+        // Step 3
+        rest = rest.trim_left_matches(HTML_SPACE_CHARACTERS);
+
+        // Step 4
+        if !rest.starts_with('=') {
+            position = content.len() - rest.len();
+            continue;
+        }
+
+        // Step 5
+        rest = rest[1..].trim_left_matches(HTML_SPACE_CHARACTERS);
+
+        // Step 6
+        let mut chars = rest.chars();
+        return match chars.next() {
+            Some(quote @ '"') | Some(quote @ '\'') => {
+                let value = &rest[1..];
+                value.find(quote).map(|end| value[..end].to_owned())
+            },
+            Some(_) => {
+                let end = rest.find(|c: char| c == ';' || HTML_SPACE_CHARACTERS.contains(&c))
+                              .unwrap_or(rest.len());
+                Some(rest[..end].to_owned())
+            },
+            None => None,
+        };
+    }
+}
+
+/// An image candidate string from a `srcset` attribute.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageCandidate {
+    pub url: String,
+    /// The pixel density descriptor, e.g. `2` for `2x`.
+    pub density: Option<f64>,
+    /// The width descriptor, e.g. `600` for `600w`.
+    pub width: Option<u32>,
+}
+
+/// Returns whether `s` is a valid floating-point number.
+///
+/// https://html.spec.whatwg.org/multipage/#valid-floating-point-number
+fn is_valid_floating_point_number(s: &str) -> bool {
+    fn skip_digits(bytes: &[u8]) -> (usize, &[u8]) {
+        let count = bytes.iter().take_while(|&&b| b >= b'0' && b <= b'9').count();
+        (count, &bytes[count..])
+    }
+
+    let mut bytes = s.as_bytes();
+    if bytes.first() == Some(&b'-') {
+        bytes = &bytes[1..];
+    }
+
+    let (integer_digits, rest) = skip_digits(bytes);
+    bytes = rest;
+    let mut fraction_digits = 0;
+    if bytes.first() == Some(&b'.') {
+        let (count, rest) = skip_digits(&bytes[1..]);
+        if count == 0 {
+            return false;
+        }
+        fraction_digits = count;
+        bytes = rest;
+    }
+    if integer_digits == 0 && fraction_digits == 0 {
+        return false;
+    }
+
+    if bytes.first() == Some(&b'e') || bytes.first() == Some(&b'E') {
+        bytes = &bytes[1..];
+        if bytes.first() == Some(&b'-') || bytes.first() == Some(&b'+') {
+            bytes = &bytes[1..];
+        }
+        let (count, rest) = skip_digits(bytes);
+        if count == 0 {
+            return false;
+        }
+        bytes = rest;
+    }
+
+    bytes.is_empty()
+}
+
+/// Parses a `srcset` attribute into its image candidates. Candidates with
+/// invalid descriptors are dropped.
+///
+/// https://html.spec.whatwg.org/multipage/#parse-a-srcset-attribute
+pub fn parse_srcset(input: &str) -> Vec<ImageCandidate> {
+    fn is_space_or_comma(c: char) -> bool {
+        c == ',' || HTML_SPACE_CHARACTERS.contains(&c)
+    }
+
+    enum State {
+        InDescriptor,
+        InParens,
+        AfterDescriptor,
+    }
+
+    let mut candidates = vec![];
+    let mut input = input;
+
+    loop {
+        // Step 3: Splitting loop
+        input = input.trim_left_matches(is_space_or_comma);
+
+        // Step 4
+        if input.is_empty() {
+            return candidates;
+        }
+
+        // Step 5
+        let url_end = input.find(|c: char| HTML_SPACE_CHARACTERS.contains(&c)).unwrap_or(input.len());
+        let mut url = &input[..url_end];
+        input = &input[url_end..];
+
+        // Step 6
+        let mut descriptors = vec![];
+
+        // Step 7
+        if url.ends_with(',') {
+            url = url.trim_right_matches(',');
+        } else {
+            // Descriptor tokenizer
+            let descriptor_input = input.trim_left_matches(HTML_SPACE_CHARACTERS);
+            let mut current_descriptor = String::new();
+            let mut state = State::InDescriptor;
+            let mut chars = descriptor_input.char_indices().peekable();
+            input = "";
+            while let Some(&(index, c)) = chars.peek() {
+                match state {
+                    State::InDescriptor => {
+                        if char_is_whitespace(c) {
+                            if !current_descriptor.is_empty() {
+                                descriptors.push(current_descriptor);
+                                current_descriptor = String::new();
+                                state = State::AfterDescriptor;
+                            }
+                        } else if c == ',' {
+                            input = &descriptor_input[index + 1..];
+                            break;
+                        } else {
+                            if c == '(' {
+                                state = State::InParens;
+                            }
+                            current_descriptor.push(c);
+                        }
+                    },
+                    State::InParens => {
+                        if c == ')' {
+                            state = State::InDescriptor;
+                        }
+                        current_descriptor.push(c);
+                    },
+                    State::AfterDescriptor => {
+                        if !char_is_whitespace(c) {
+                            // Reconsume the character in the descriptor state.
+                            state = State::InDescriptor;
+                            continue;
+                        }
+                    },
+                }
+                chars.next();
+            }
+            if !current_descriptor.is_empty() {
+                descriptors.push(current_descriptor);
+            }
+        }
+
+        // Step 8: Descriptor parser
+        let mut error = false;
+        let mut width = None;
+        let mut density = None;
+        let mut future_compat_h = None;
+        for descriptor in &descriptors {
+            let last_char_index = descriptor.char_indices().last().map_or(0, |(index, _)| index);
+            let (value, kind) = descriptor.split_at(last_char_index);
+            let value_is_integer = !value.is_empty() && value.bytes().all(|b| b >= b'0' && b <= b'9');
+            match kind {
+                "w" => {
+                    if width.is_some() || density.is_some() || !value_is_integer {
+                        error = true;
+                        continue;
+                    }
+                    match parse_unsigned_integer(value.chars()) {
+                        Some(0) | None => error = true,
+                        result => width = result,
+                    }
+                },
+                "x" => {
+                    if width.is_some() || density.is_some() || future_compat_h.is_some() ||
+                       !is_valid_floating_point_number(value) {
+                        error = true;
+                        continue;
+                    }
+                    match f64::from_str(value) {
+                        Ok(result) if result >= 0. => density = Some(result),
+                        _ => error = true,
+                    }
+                },
+                "h" => {
+                    if future_compat_h.is_some() || density.is_some() || !value_is_integer {
+                        error = true;
+                        continue;
+                    }
+                    match parse_unsigned_integer(value.chars()) {
+                        Some(0) | None => error = true,
+                        result => future_compat_h = result,
+                    }
+                },
+                _ => error = true,
+            }
+        }
+        if future_compat_h.is_some() && width.is_none() {
+            error = true;
+        }
+        if !error {
+            candidates.push(ImageCandidate {
+                url: url.to_owned(),
+                density: density,
+                width: width,
+            });
+        }
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// Returns the number of days in `month` (from 1 to 12) of `year`, or `None`
+/// if `month` is out of range.
+fn days_in_month(year: i32, month: u32) -> Option<u32> {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => Some(31),
+        4 | 6 | 9 | 11 => Some(30),
+        2 if is_leap_year(year) => Some(29),
+        2 => Some(28),
+        _ => None,
+    }
+}
+
+/// Resolves the two-digit year of an RFC 850 date relative to
+/// `current_year`. As [RFC 7231](https://tools.ietf.org/html/rfc7231#section-7.1.1.1)
+/// requires, a year which would be more than 50 years in the future is
+/// taken to be the most recent past year with the same last two digits;
+/// otherwise, it is the nearest year with those digits.
+pub fn resolve_two_digit_year(two_digit_year: u32, current_year: i32) -> i32 {
+    let century = current_year - ((current_year % 100) + 100) % 100;
+    let year = century + two_digit_year as i32;
+    if year > current_year + 50 {
+        year - 100
+    } else if year <= current_year - 50 {
+        year + 100
+    } else {
+        year
+    }
+}
+
+/// Parses the value of an HTTP date header in any of the three formats of
+/// [RFC 7231](https://tools.ietf.org/html/rfc7231#section-7.1.1.1), returning
+/// the number of seconds since the Unix epoch:
+///
+/// * IMF-fixdate: `Sun, 06 Nov 1994 08:49:37 GMT`
+/// * RFC 850: `Sunday, 06-Nov-94 08:49:37 GMT`
+/// * asctime: `Sun Nov  6 08:49:37 1994`
+///
+/// Two-digit RFC 850 years are resolved relative to `current_year`, as per
+/// `resolve_two_digit_year`; callers pass the year at the time the header was
+/// received, so that parsing does not depend on the clock.
+pub fn parse_http_date(value: &str, current_year: i32) -> Option<i64> {
+    static DAY_NAMES: StaticStringVec = &["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    static LONG_DAY_NAMES: StaticStringVec =
+        &["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+    static MONTH_NAMES: StaticStringVec =
+        &["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+    fn parse_month(name: &str) -> Option<u32> {
+        MONTH_NAMES.iter().position(|&month| month == name).map(|index| index as u32 + 1)
+    }
+
+    fn parse_day(day: &str) -> Option<u32> {
+        match day.len() {
+            1 | 2 => parse_fixed_width_integer(day, day.len()),
+            _ => None,
+        }
+    }
+
+    fn parse_time_of_day(time: &str) -> Option<(u32, u32, u32)> {
+        let components: Vec<&str> = time.split(':').collect();
+        if components.len() != 3 {
+            return None;
+        }
+        match (parse_fixed_width_integer(components[0], 2),
+               parse_fixed_width_integer(components[1], 2),
+               parse_fixed_width_integer(components[2], 2)) {
+            (Some(hour), Some(minute), Some(second)) if hour < 24 && minute < 60 && second <= 60 => {
+                Some((hour, minute, second))
+            },
+            _ => None,
+        }
+    }
+
+    let tokens: Vec<&str> = value.split_whitespace().collect();
+    let (year, month, day, time) = match tokens.len() {
+        // IMF-fixdate
+        6 if tokens[0].ends_with(',') && DAY_NAMES.contains(&&tokens[0][..tokens[0].len() - 1]) &&
+             tokens[5] == "GMT" => {
+            (parse_fixed_width_integer(tokens[3], 4).map(|year| year as i32),
+             parse_month(tokens[2]),
+             parse_fixed_width_integer(tokens[1], 2),
+             tokens[4])
+        },
+        // RFC 850
+        4 if tokens[0].ends_with(',') &&
+             LONG_DAY_NAMES.contains(&&tokens[0][..tokens[0].len() - 1]) && tokens[3] == "GMT" => {
+            let date: Vec<&str> = tokens[1].split('-').collect();
+            if date.len() != 3 {
+                return None;
+            }
+            let year = parse_fixed_width_integer(date[2], 2).map(|year| {
+                resolve_two_digit_year(year, current_year)
+            });
+            (year, parse_month(date[1]), parse_fixed_width_integer(date[0], 2), tokens[2])
+        },
+        // asctime
+        5 if DAY_NAMES.contains(&tokens[0]) => {
+            (parse_fixed_width_integer(tokens[4], 4).map(|year| year as i32),
+             parse_month(tokens[1]),
+             parse_day(tokens[2]),
+             tokens[3])
+        },
+        _ => return None,
+    };
+
+    let (year, month, day) = match (year, month, day) {
+        (Some(year), Some(month), Some(day))
+                if day >= 1 && days_in_month(year, month).map_or(false, |days| day <= days) => {
+            (year as i64, month as i64, day as i64)
+        },
+        _ => return None,
+    };
+    let (hour, minute, second) = match parse_time_of_day(time) {
+        Some(time) => time,
+        None => return None,
+    };
+
+    // Count the days since the epoch, with years starting on March 1st so
+    // that leap days fall at the end of the year.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    Some(days * 86400 + hour as i64 * 3600 + minute as i64 * 60 + second as i64)
+}
+
+/// Parses a literal `true` or `false` value, ASCII case-insensitively, as
+/// used by ARIA states. Unlike HTML boolean attributes, any other value
+/// (including the empty string) is invalid.
+pub fn parse_ascii_bool(value: &str) -> Option<bool> {
+    if value.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if value.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn is_css_name_start_char(c: char) -> bool {
+    match c {
+        'a'...'z' | 'A'...'Z' | '_' => true,
+        _ => !c.is_ascii(),
+    }
+}
+
+fn is_css_name_char(c: char) -> bool {
+    match c {
+        '0'...'9' | '-' => true,
+        _ => is_css_name_start_char(c),
+    }
+}
+
+fn is_css_newline(c: char) -> bool {
+    c == '\n' || c == '\r' || c == '\x0c'
+}
+
+/// Returns whether the two chars at the front of `input` form a valid CSS
+/// escape.
+///
+/// https://drafts.csswg.org/css-syntax/#starts-with-a-valid-escape
+fn starts_with_css_escape(input: &str) -> bool {
+    let mut chars = input.chars();
+    chars.next() == Some('\\') && !chars.next().map_or(false, is_css_newline)
+}
+
+/// Consumes an escaped code point from the front of `input`, just after the
+/// backslash, returning the code point and the remainder of `input`.
+///
+/// https://drafts.csswg.org/css-syntax/#consume-an-escaped-code-point
+fn consume_css_escape(input: &str) -> (char, &str) {
+    let hex_length = input.chars().take(6).take_while(|c| c.is_digit(16)).count();
+    if hex_length == 0 {
+        return match input.chars().next() {
+            Some(c) => (c, &input[c.len_utf8()..]),
+            None => ('\u{fffd}', input),
+        };
+    }
+
+    let (hex, mut rest) = input.split_at(hex_length);
+    // A single whitespace character (including a CRLF pair) after a hex escape
+    // is consumed.
+    if rest.starts_with("\r\n") {
+        rest = &rest[2..];
+    } else if rest.starts_with(|c: char| c == ' ' || c == '\t' || is_css_newline(c)) {
+        rest = &rest[1..];
+    }
+    let c = match u32::from_str_radix(hex, 16).ok().and_then(::std::char::from_u32) {
+        Some('\0') | None => '\u{fffd}',
+        Some(c) => c,
+    };
+    (c, rest)
+}
+
+/// Consumes a CSS identifier from the front of `input`, returning its value
+/// with escapes decoded, and the remainder of `input`. Returns `None` if
+/// `input` does not start with an identifier.
+///
+/// https://drafts.csswg.org/css-syntax/#consume-a-name
+pub fn parse_css_identifier(input: &str) -> Option<(String, &str)> {
+    // https://drafts.csswg.org/css-syntax/#would-start-an-identifier
+    let starts_identifier = match input.chars().next() {
+        Some('-') => {
+            let rest = &input[1..];
+            match rest.chars().next() {
+                Some('-') => true,
+                Some(c) if is_css_name_start_char(c) => true,
+                _ => starts_with_css_escape(rest),
+            }
+        },
+        Some(c) if is_css_name_start_char(c) => true,
+        _ => starts_with_css_escape(input),
+    };
+    if !starts_identifier {
+        return None;
+    }
+
+    let mut result = String::new();
+    let mut rest = input;
+    loop {
+        match rest.chars().next() {
+            Some(c) if is_css_name_char(c) => {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+            },
+            _ if starts_with_css_escape(rest) => {
+                let (c, remainder) = consume_css_escape(&rest[1..]);
+                result.push(c);
+                rest = remainder;
+            },
+            _ => return Some((result, rest)),
+        }
+    }
+}
+
+/// The `shape` of an `<area>` element.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AreaShape {
+    Rect,
+    Circle,
+    Poly,
+    Default,
+}
+
+/// Parses the `coords` attribute of an `<area>` element as a list of
+/// floating-point numbers, and checks that the number of coordinates suits
+/// `shape`: exactly four for a rectangle, exactly three for a circle, and an
+/// even number of at least six for a polygon. Any number is accepted for the
+/// default shape.
+///
+/// https://html.spec.whatwg.org/multipage/#rules-for-parsing-a-list-of-floating-point-numbers
+pub fn parse_area_coords(shape: AreaShape, coords: &str) -> Option<Vec<f64>> {
+    fn is_separator(c: char) -> bool {
+        c == ',' || c == ';' || char_is_whitespace(c)
+    }
+
+    let numbers: Vec<f64> = coords.split(is_separator)
+                                  .filter(|number| !number.is_empty())
+                                  .map(|number| parse_floating_point_number(number).unwrap_or(0.))
+                                  .collect();
+
+    let valid = match shape {
+        AreaShape::Rect => numbers.len() == 4,
+        AreaShape::Circle => numbers.len() == 3,
+        AreaShape::Poly => numbers.len() >= 6 && numbers.len() % 2 == 0,
+        AreaShape::Default => true,
+    };
+    if valid {
+        Some(numbers)
+    } else {
+        None
+    }
+}
+
+/// Parses a list of integers separated by commas, semicolons or whitespace,
+/// appending them to `out` so that its buffer can be reused across calls.
+/// Each item is parsed with `parse_integer`; items which are not integers
+/// are taken to be zero, as in `parse_area_coords`.
+pub fn parse_integer_list_into(input: &str, out: &mut Vec<i32>) {
+    fn is_separator(c: char) -> bool {
+        c == ',' || c == ';' || char_is_whitespace(c)
+    }
+
+    out.extend(input.split(is_separator)
+                    .filter(|number| !number.is_empty())
+                    .map(|number| parse_integer(number.chars()).unwrap_or(0)));
+}
+
+/// Parses a set of space-separated keywords, such as the value of the
+/// `sandbox` attribute, returning the entries of `known` which match a token
+/// ASCII case-insensitively. Unknown tokens are ignored.
+pub fn parse_keyword_set(value: &str, known: &[&'static str]) -> HashSet<&'static str> {
+    split_html_space_chars(value).filter_map(|token| {
+        known.iter().find(|keyword| keyword.eq_ignore_ascii_case(token)).map(|&keyword| keyword)
+    }).collect()
+}
+
+bitflags! {
+    #[doc = "The sandboxing flag set of a browsing context. Each set flag is a restriction."]
+    #[doc = ""]
+    #[doc = "https://html.spec.whatwg.org/multipage/#sandboxing-flag-set"]
+    flags SandboxFlags: u32 {
+        const SANDBOXED_NAVIGATION = 0x001,
+        const SANDBOXED_AUXILIARY_NAVIGATION = 0x002,
+        const SANDBOXED_TOP_LEVEL_NAVIGATION = 0x004,
+        const SANDBOXED_PLUGINS = 0x008,
+        const SANDBOXED_ORIGIN = 0x010,
+        const SANDBOXED_FORMS = 0x020,
+        const SANDBOXED_POINTER_LOCK = 0x040,
+        const SANDBOXED_SCRIPTS = 0x080,
+        const SANDBOXED_AUTOMATIC_FEATURES = 0x100,
+        const SANDBOXED_MODALS = 0x200,
+        const SANDBOX_PROPAGATES_TO_AUXILIARY_BROWSING_CONTEXTS = 0x400,
+        const SANDBOXED_ORIENTATION_LOCK = 0x800,
+        const SANDBOXED_PRESENTATION = 0x1000,
+    }
+}
+
+/// Parses the value of an iframe's `sandbox` attribute. All restrictions are
+/// set, except for those lifted by the `allow-*` keywords in `value`; other
+/// tokens are ignored.
+///
+/// https://html.spec.whatwg.org/multipage/#parse-a-sandboxing-directive
+pub fn parse_sandbox(value: &str) -> SandboxFlags {
+    static KEYWORDS: &'static [&'static str] = &[
+        "allow-forms",
+        "allow-modals",
+        "allow-orientation-lock",
+        "allow-pointer-lock",
+        "allow-popups",
+        "allow-popups-to-escape-sandbox",
+        "allow-presentation",
+        "allow-same-origin",
+        "allow-scripts",
+        "allow-top-navigation",
+    ];
+
+    let mut flags = SandboxFlags::all();
+    for keyword in parse_keyword_set(value, KEYWORDS) {
+        flags.remove(match keyword {
+            "allow-forms" => SANDBOXED_FORMS,
+            "allow-modals" => SANDBOXED_MODALS,
+            "allow-orientation-lock" => SANDBOXED_ORIENTATION_LOCK,
+            "allow-pointer-lock" => SANDBOXED_POINTER_LOCK,
+            "allow-popups" => SANDBOXED_AUXILIARY_NAVIGATION,
+            "allow-popups-to-escape-sandbox" => SANDBOX_PROPAGATES_TO_AUXILIARY_BROWSING_CONTEXTS,
+            "allow-presentation" => SANDBOXED_PRESENTATION,
+            "allow-same-origin" => SANDBOXED_ORIGIN,
+            "allow-scripts" => SANDBOXED_SCRIPTS | SANDBOXED_AUTOMATIC_FEATURES,
+            "allow-top-navigation" => SANDBOXED_TOP_LEVEL_NAVIGATION,
+            _ => unreachable!(),
+        });
+    }
+    flags
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    Ltr,
+    Rtl,
+}
+
+/// Returns the direction of the first character of `s` with a strong bidi
+/// class (L, R or AL), or `None` if there is no such character. This is the
+/// core of the `dir=auto` algorithm.
+///
+/// https://html.spec.whatwg.org/multipage/#the-directionality
+pub fn first_strong_direction(s: &str) -> Option<Direction> {
+    s.chars().filter_map(|c| {
+        match bidi_class(c) {
+            BidiClass::L => Some(Direction::Ltr),
+            BidiClass::R | BidiClass::AL => Some(Direction::Rtl),
+            _ => None,
+        }
+    }).next()
+}
+
+/// Returns whether `s` contains any of the explicit bidi formatting
+/// characters (LRE, RLE, PDF, LRO, RLO, LRI, RLI, FSI and PDI), which can be
+/// used to disguise text such as URLs.
+///
+/// http://www.unicode.org/reports/tr9/#Explicit_Directional_Embeddings
+pub fn contains_bidi_control(s: &str) -> bool {
+    s.chars().any(|c| match c {
+        '\u{202a}'...'\u{202e}' | '\u{2066}'...'\u{2069}' => true,
+        _ => false,
+    })
+}
+
+/// Parses the `content` attribute of a `<meta name=viewport>` element into
+/// its `key=value` pairs, as written. Properties are separated by commas,
+/// semicolons or whitespace, and whitespace is allowed around `=`. A property
+/// with no value is returned with an empty one.
+///
+/// https://drafts.csswg.org/css-device-adapt/#parsing-algorithm
+pub fn parse_viewport(content: &str) -> Vec<(String, String)> {
+    fn is_whitespace(c: char) -> bool {
+        c == ' ' || c == '\t' || c == '\n' || c == '\r'
+    }
+    fn is_separator(c: char) -> bool {
+        c == ',' || c == ';' || is_whitespace(c)
+    }
+    fn is_token_end(c: char) -> bool {
+        c == '=' || is_separator(c)
+    }
+    fn split_token(input: &str) -> (&str, &str) {
+        let end = input.find(is_token_end).unwrap_or(input.len());
+        input.split_at(end)
+    }
+
+    let mut properties = vec![];
+    let mut rest = content;
+    loop {
+        rest = rest.trim_left_matches(|c: char| c == '=' || is_separator(c));
+        if rest.is_empty() {
+            return properties;
+        }
+
+        let (key, after_key) = split_token(rest);
+        rest = after_key.trim_left_matches(is_whitespace);
+
+        let mut value = "";
+        if rest.starts_with('=') {
+            let (token, after_value) = split_token(rest[1..].trim_left_matches(is_whitespace));
+            value = token;
+            rest = after_value;
+        }
+        properties.push((key.to_owned(), value.to_owned()));
+    }
+}
+
+/// The values of the CSS `white-space` property.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WhiteSpaceMode {
+    Normal,
+    Pre,
+    PreLine,
+    PreWrap,
+    NoWrap,
+}
+
+/// Collapses the white space (spaces, tabs and line feeds) of `s` as the
+/// given `white-space` mode requires. `Pre` and `PreWrap` preserve it all;
+/// `PreLine` collapses each run into its line feeds, or a single space if it
+/// has none; `Normal` and `NoWrap` collapse each run into a single space.
+///
+/// https://drafts.csswg.org/css-text-3/#white-space-phase-1
+pub fn process_whitespace(s: &str, mode: WhiteSpaceMode) -> String {
+    let preserve_line_feeds = match mode {
+        WhiteSpaceMode::Pre | WhiteSpaceMode::PreWrap => return s.to_owned(),
+        WhiteSpaceMode::PreLine => true,
+        WhiteSpaceMode::Normal | WhiteSpaceMode::NoWrap => false,
+    };
+
+    fn is_white_space(c: char) -> bool {
+        c == ' ' || c == '\t' || c == '\n'
+    }
+
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if !is_white_space(c) {
+            result.push(c);
+            continue;
+        }
+
+        let mut line_feeds = if c == '\n' { 1 } else { 0 };
+        while chars.peek().map_or(false, |&next| is_white_space(next)) {
+            if chars.next() == Some('\n') {
+                line_feeds += 1;
+            }
+        }
+        if preserve_line_feeds && line_feeds > 0 {
+            for _ in 0..line_feeds {
+                result.push('\n');
+            }
+        } else {
+            result.push(' ');
+        }
+    }
+    result
+}
+
+/// Splits the leading run of ASCII digits off `input`.
+fn split_ascii_digits(input: &str) -> (&str, &str) {
+    let end = input.find(|c: char| !c.is_digit(10)).unwrap_or(input.len());
+    input.split_at(end)
+}
+
+/// Parses a year of four or more digits, greater than zero, at the start of
+/// `input`. Returns the year and the rest of `input`.
+fn parse_year_component(input: &str) -> Option<(i32, &str)> {
+    let (year, rest) = split_ascii_digits(input);
+    if year.len() < 4 {
+        return None;
+    }
+    match parse_fixed_width_integer(year, year.len()) {
+        Some(year) if year >= 1 && year <= i32::max_value() as u32 => Some((year as i32, rest)),
+        _ => None,
+    }
+}
+
+/// Parses a month component, i.e. a year of four or more digits greater
+/// than zero, a hyphen and a two-digit month, at the start of `input`.
+/// Returns the year, the month and the rest of `input`.
+///
+/// https://html.spec.whatwg.org/multipage/#parse-a-month-component
+fn parse_month_component(input: &str) -> Option<(i32, u32, &str)> {
+    let (year, rest) = match parse_year_component(input) {
+        Some((year, rest)) if rest.starts_with('-') => (year, rest),
+        _ => return None,
+    };
+    let (month, rest) = split_ascii_digits(&rest[1..]);
+    match parse_fixed_width_integer(month, 2) {
+        Some(month) if month >= 1 && month <= 12 => Some((year, month, rest)),
+        _ => None,
+    }
+}
+
+/// Parses a valid month string, returning its year and month.
+///
+/// https://html.spec.whatwg.org/multipage/#parse-a-month-string
+pub fn parse_month_string(s: &str) -> Option<(i32, u32)> {
+    match parse_month_component(s) {
+        Some((year, month, "")) => Some((year, month)),
+        _ => None,
+    }
+}
+
+/// Parses a valid week string, returning its year and week. The week must
+/// exist in that year, i.e. it is at most 52, or 53 in years that begin on a
+/// Thursday and in leap years that begin on a Wednesday.
+///
+/// https://html.spec.whatwg.org/multipage/#parse-a-week-string
+pub fn parse_week_string(s: &str) -> Option<(i32, u32)> {
+    let (year, rest) = match parse_year_component(s) {
+        Some((year, rest)) if rest.starts_with("-W") => (year, rest),
+        _ => return None,
+    };
+
+    // Gauss's algorithm for the day of the week of January 1st, from Sunday
+    // (0) to Saturday (6).
+    let previous = year as i64 - 1;
+    let first_weekday = (1 + 5 * (previous % 4) + 4 * (previous % 100) + 6 * (previous % 400)) % 7;
+    let max_week = match first_weekday {
+        4 => 53,
+        3 if is_leap_year(year) => 53,
+        _ => 52,
+    };
+
+    match parse_fixed_width_integer(&rest[2..], 2) {
+        Some(week) if week >= 1 && week <= max_week => Some((year, week)),
+        _ => None,
+    }
+}
+
+/// Parses a time component, i.e. a two-digit hour and minute separated by a
+/// colon, optionally followed by a colon, a two-digit second and a fraction
+/// of one to three digits, at the start of `input`. Returns the hour, the
+/// minute, the second, the millisecond and the rest of `input`.
+///
+/// https://html.spec.whatwg.org/multipage/#parse-a-time-component
+fn parse_time_component(input: &str) -> Option<(u32, u32, u32, u32, &str)> {
+    fn parse_two_digits(input: &str, maximum: u32) -> Option<(u32, &str)> {
+        let (digits, rest) = split_ascii_digits(input);
+        match parse_fixed_width_integer(digits, 2) {
+            Some(value) if value <= maximum => Some((value, rest)),
+            _ => None,
+        }
+    }
+
+    let (hour, rest) = match parse_two_digits(input, 23) {
+        Some((hour, rest)) if rest.starts_with(':') => (hour, rest),
+        _ => return None,
+    };
+    let (minute, rest) = match parse_two_digits(&rest[1..], 59) {
+        Some(minute) => minute,
+        None => return None,
+    };
+    if !rest.starts_with(':') {
+        return Some((hour, minute, 0, 0, rest));
+    }
+
+    let (second, rest) = match parse_two_digits(&rest[1..], 59) {
+        Some(second) => second,
+        None => return None,
+    };
+    if !rest.starts_with('.') {
+        return Some((hour, minute, second, 0, rest));
+    }
+
+    let (fraction, rest) = split_ascii_digits(&rest[1..]);
+    if fraction.len() > 3 {
+        return None;
+    }
+    parse_fixed_width_integer(fraction, fraction.len()).map(|value| {
+        let millisecond = value * 10u32.pow(3 - fraction.len() as u32);
+        (hour, minute, second, millisecond, rest)
+    })
+}
+
+/// Parses a valid time string, returning its hour, minute, second and
+/// millisecond.
+///
+/// https://html.spec.whatwg.org/multipage/#parse-a-time-string
+pub fn parse_time_string(s: &str) -> Option<(u32, u32, u32, u32)> {
+    match parse_time_component(s) {
+        Some((hour, minute, second, millisecond, "")) => Some((hour, minute, second, millisecond)),
+        _ => None,
+    }
+}
+
+/// Parses a date component, i.e. a month component followed by a hyphen and
+/// a two-digit day that exists in that month, at the start of `input`.
+/// Returns the year, the month, the day and the rest of `input`.
+///
+/// https://html.spec.whatwg.org/multipage/#parse-a-date-component
+fn parse_date_component(input: &str) -> Option<(i32, u32, u32, &str)> {
+    let (year, month, rest) = match parse_month_component(input) {
+        Some((year, month, rest)) if rest.starts_with('-') => (year, month, rest),
+        _ => return None,
+    };
+    let (day, rest) = split_ascii_digits(&rest[1..]);
+    match parse_fixed_width_integer(day, 2) {
+        Some(day) if day >= 1 && days_in_month(year, month).map_or(false, |days| day <= days) => {
+            Some((year, month, day, rest))
+        },
+        _ => None,
+    }
+}
+
+/// Parses a valid date string, returning its year, month and day.
+///
+/// https://html.spec.whatwg.org/multipage/#parse-a-date-string
+pub fn parse_date_string(s: &str) -> Option<(i32, u32, u32)> {
+    match parse_date_component(s) {
+        Some((year, month, day, "")) => Some((year, month, day)),
+        _ => None,
+    }
+}
+
+/// Parses a valid local date and time string, i.e. a date and a time
+/// separated by a `T` or a space, returning its year, month, day, hour,
+/// minute, second and millisecond.
+///
+/// https://html.spec.whatwg.org/multipage/#parse-a-local-date-and-time-string
+pub fn parse_datetime_local(s: &str) -> Option<(i32, u32, u32, u32, u32, u32, u32)> {
+    let (year, month, day, rest) = match parse_date_component(s) {
+        Some((year, month, day, rest)) if rest.starts_with('T') || rest.starts_with(' ') => {
+            (year, month, day, rest)
+        },
+        _ => return None,
+    };
+    match parse_time_component(&rest[1..]) {
+        Some((hour, minute, second, millisecond, "")) => {
+            Some((year, month, day, hour, minute, second, millisecond))
+        },
+        _ => None,
+    }
+}
+
+/// Returns whether `s` is a CSS `<integer>`: an optional sign followed by one
+/// or more ASCII digits, with nothing else (not even whitespace) around them.
+///
+/// https://drafts.csswg.org/css-syntax/#consume-a-number
+pub fn is_css_integer(s: &str) -> bool {
+    let digits = if s.starts_with('+') || s.starts_with('-') { &s[1..] } else { s };
+    !digits.is_empty() && digits.bytes().all(|b| b >= b'0' && b <= b'9')
+}
+
+/// Parses a CSS `<integer>`, as per `is_css_integer`. Returns `None` if `s`
+/// is not one, or if it does not fit in an `i32`.
+pub fn parse_css_integer(s: &str) -> Option<i32> {
+    if !is_css_integer(s) {
+        return None;
+    }
+    i32::from_str(s).ok()
+}
+
+/// Returns whether `s` is usable as the name of a form field in a form data
+/// set:
+///
+/// * it must not be empty, since fields with an empty name are skipped when
+///   constructing the form data set;
+/// * it must not contain control characters (including CR and LF), which
+///   would corrupt the `multipart/form-data` headers the name is written to.
+///
+/// https://html.spec.whatwg.org/multipage/#constructing-the-form-data-set
+pub fn is_valid_form_field_name(s: &str) -> bool {
+    !s.is_empty() && !s.chars().any(|c| c.is_control())
+}
+
+/// The named character references decoded by `decode_named_char_refs`. This
+/// is only a commonly-used subset of the full table.
+///
+/// https://html.spec.whatwg.org/multipage/#named-character-references
+static NAMED_CHAR_REFS: &'static [(&'static str, char)] = &[
+    ("amp", '&'),
+    ("apos", '\''),
+    ("bull", '\u{2022}'),
+    ("cent", '\u{a2}'),
+    ("copy", '\u{a9}'),
+    ("deg", '\u{b0}'),
+    ("euro", '\u{20ac}'),
+    ("gt", '>'),
+    ("hellip", '\u{2026}'),
+    ("laquo", '\u{ab}'),
+    ("ldquo", '\u{201c}'),
+    ("lsquo", '\u{2018}'),
+    ("lt", '<'),
+    ("mdash", '\u{2014}'),
+    ("middot", '\u{b7}'),
+    ("nbsp", '\u{a0}'),
+    ("ndash", '\u{2013}'),
+    ("para", '\u{b6}'),
+    ("pound", '\u{a3}'),
+    ("quot", '"'),
+    ("raquo", '\u{bb}'),
+    ("rdquo", '\u{201d}'),
+    ("reg", '\u{ae}'),
+    ("rsquo", '\u{2019}'),
+    ("sect", '\u{a7}'),
+    ("times", '\u{d7}'),
+    ("trade", '\u{2122}'),
+    ("yen", '\u{a5}'),
+];
+
+/// Replaces each named character reference in `s` that is terminated by a
+/// semicolon and appears in `NAMED_CHAR_REFS` with the character it stands
+/// for. Any other ampersand is left as it is.
+pub fn decode_named_char_refs(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(index) = rest.find('&') {
+        result.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        let name_length = rest[1..].find(|c: char| !c.is_alphanumeric() || !c.is_ascii())
+                                   .unwrap_or(rest.len() - 1);
+        let name = &rest[1..1 + name_length];
+        let decoded = if rest[1 + name_length..].starts_with(';') {
+            NAMED_CHAR_REFS.iter().find(|&&(entry, _)| entry == name).map(|&(_, ch)| ch)
+        } else {
+            None
+        };
+        match decoded {
+            Some(ch) => {
+                result.push(ch);
+                rest = &rest[name_length + 2..];
+            },
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Serializes `n` as the best representation of the number as a
+/// floating-point number, i.e. as ECMAScript's `ToString` would: the shortest
+/// decimal which round-trips, in exponential notation only if its exponent
+/// is below -6 or at least 21.
+///
+/// https://html.spec.whatwg.org/multipage/#best-representation-of-the-number-as-a-floating-point-number
+/// http://www.ecma-international.org/ecma-262/6.0/#sec-tostring-applied-to-the-number-type
+pub fn best_float_representation(n: f64) -> String {
+    if n.is_nan() {
+        return "NaN".to_owned();
+    }
+    if n.is_infinite() {
+        return if n > 0. { "Infinity" } else { "-Infinity" }.to_owned();
+    }
+    if n == 0. {
+        return "0".to_owned();
+    }
+
+    // `{:e}` gives the shortest round-tripping digits, as `d.ddde-x`.
+    let exponential = format!("{:e}", n.abs());
+    let (mantissa, exponent) = split_once_char(&exponential, 'e').unwrap();
+    let digits: String = mantissa.chars().filter(|&c| c != '.').collect();
+    let digit_count = digits.len() as i32;
+    // The position of the decimal point relative to the start of `digits`.
+    let point = i32::from_str(exponent).unwrap() + 1;
+
+    let mut result = String::new();
+    if n < 0. {
+        result.push('-');
+    }
+    if digit_count <= point && point <= 21 {
+        result.push_str(&digits);
+        for _ in 0..point - digit_count {
+            result.push('0');
+        }
+    } else if 0 < point && point <= 21 {
+        result.push_str(&digits[..point as usize]);
+        result.push('.');
+        result.push_str(&digits[point as usize..]);
+    } else if -6 < point && point <= 0 {
+        result.push_str("0.");
+        for _ in 0..-point {
+            result.push('0');
+        }
+        result.push_str(&digits);
+    } else {
+        result.push_str(&digits[..1]);
+        if digit_count > 1 {
+            result.push('.');
+            result.push_str(&digits[1..]);
+        }
+        result.push_str(&format!("e{}{}", if point > 0 { '+' } else { '-' }, (point - 1).abs()));
+    }
+    result
+}
+
+/// Splits `s` into words, for simple searching and indexing. A word is a
+/// maximal run of alphanumeric characters: whitespace, punctuation and
+/// symbols all separate words, so `"don't"` is the two words `"don"` and
+/// `"t"`.
+pub fn split_words<'a>(s: &'a str) -> Filter<Split<'a, fn(char) -> bool>, fn(&&str) -> bool> {
+    fn is_separator(c: char) -> bool { !c.is_alphanumeric() }
+    fn not_empty(&split: &&str) -> bool { !split.is_empty() }
+    s.split(is_separator as fn(char) -> bool).filter(not_empty as fn(&&str) -> bool)
+}
+
+/// Consumes a CSS number from the front of `input`, returning its value and
+/// the remainder of `input`. Returns `None` if `input` does not start with a
+/// number.
+///
+/// https://drafts.csswg.org/css-syntax/#consume-a-number
+fn consume_css_number(input: &str) -> Option<(f64, &str)> {
+    fn digit_count(bytes: &[u8]) -> usize {
+        bytes.iter().take_while(|&&b| b >= b'0' && b <= b'9').count()
+    }
+
+    let bytes = input.as_bytes();
+    let mut end = 0;
+    if end < bytes.len() && (bytes[end] == b'+' || bytes[end] == b'-') {
+        end += 1;
+    }
+    let integer_digits = digit_count(&bytes[end..]);
+    end += integer_digits;
+    let mut fraction_digits = 0;
+    if end + 1 < bytes.len() && bytes[end] == b'.' {
+        fraction_digits = digit_count(&bytes[end + 1..]);
+        if fraction_digits > 0 {
+            end += 1 + fraction_digits;
+        }
+    }
+    if integer_digits == 0 && fraction_digits == 0 {
+        return None;
+    }
+
+    // An exponent is only consumed if it has digits, so that `1em` is one em
+    // rather than a malformed exponent.
+    if end < bytes.len() && (bytes[end] == b'e' || bytes[end] == b'E') {
+        let mut exponent_end = end + 1;
+        if exponent_end < bytes.len() && (bytes[exponent_end] == b'+' || bytes[exponent_end] == b'-') {
+            exponent_end += 1;
+        }
+        let exponent_digits = digit_count(&bytes[exponent_end..]);
+        if exponent_digits > 0 {
+            end = exponent_end + exponent_digits;
+        }
+    }
+
+    f64::from_str(&input[..end]).ok().map(|value| (value, &input[end..]))
+}
+
+/// Parses a CSS `<number>`: an optional sign, digits with an optional
+/// fraction (or just a fraction, as in `.5`) and an optional exponent, with
+/// nothing else around them. A trailing full stop is not part of a number,
+/// so `1.` is not valid.
+pub fn parse_css_number(s: &str) -> Option<f64> {
+    match consume_css_number(s) {
+        Some((value, "")) => Some(value),
+        _ => None,
+    }
+}
+
+/// Parses a list of CSS `<number>`s separated by commas and/or whitespace.
+/// Items which are not numbers, as per `parse_css_number`, are skipped.
+pub fn parse_float_list(input: &str) -> Vec<f64> {
+    input.split(|c: char| c == ',' || char_is_whitespace(c))
+         .filter_map(parse_css_number)
+         .collect()
+}
+
+/// Parses a CSS dimension such as `10px` or `1.5em`, or a percentage such as
+/// `50%`, returning its value and its unit as written (`%` for a
+/// percentage). Returns `None` for anything else, including a number without
+/// a unit.
+///
+/// https://drafts.csswg.org/css-syntax/#consume-a-numeric-token
+pub fn parse_css_dimension(input: &str) -> Option<(f64, String)> {
+    let (value, rest) = match consume_css_number(input) {
+        Some(number) => number,
+        None => return None,
+    };
+    if rest == "%" {
+        return Some((value, "%".to_owned()));
+    }
+    match parse_css_identifier(rest) {
+        Some((unit, "")) => Some((value, unit)),
+        _ => None,
+    }
+}
+
+/// Percent-decodes the fragment of a URL, decoding the result as UTF-8 with
+/// replacement characters, for matching against element ids when finding the
+/// indicated part of the document.
+///
+/// https://html.spec.whatwg.org/multipage/#the-indicated-part-of-the-document
+pub fn decode_fragment(fragment: &str) -> String {
+    lossy_utf8_percent_decode(fragment.as_bytes())
+}
+
+/// Consumes a CSS string token, delimited by single or double quotes, from
+/// the front of `input`. Returns its value with escapes decoded and escaped
+/// newlines removed, and the remainder of `input` after the closing quote.
+/// As in CSS, a string left unterminated at the end of `input` is closed
+/// implicitly; one interrupted by an unescaped newline is a bad string, for
+/// which `None` is returned.
+///
+/// https://drafts.csswg.org/css-syntax/#consume-a-string-token
+pub fn parse_css_string(input: &str) -> Option<(String, &str)> {
+    let quote = match input.chars().next() {
+        Some(c) if c == '"' || c == '\'' => c,
+        _ => return None,
+    };
+
+    let mut result = String::new();
+    let mut rest = &input[1..];
+    loop {
+        let c = match rest.chars().next() {
+            Some(c) => c,
+            None => return Some((result, rest)),
+        };
+        if c == quote {
+            return Some((result, &rest[1..]));
+        }
+        if is_css_newline(c) {
+            return None;
+        }
+        if c != '\\' {
+            result.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let escaped = &rest[1..];
+        rest = if escaped.starts_with("\r\n") {
+            &escaped[2..]
+        } else if escaped.starts_with(is_css_newline) {
+            &escaped[1..]
+        } else if escaped.is_empty() {
+            escaped
+        } else {
+            let (c, remainder) = consume_css_escape(escaped);
+            result.push(c);
+            remainder
+        };
+    }
+}
+
+/// Parses the `content` of a `<meta http-equiv=refresh>` element (or the
+/// value of a `Refresh` header), e.g. `5; url=next.html`, returning the delay
+/// in seconds and the URL to navigate to, if any. Returns `None` if `content`
+/// does not start with a delay.
+///
+/// https://html.spec.whatwg.org/multipage/#attr-meta-http-equiv-refresh
+pub fn parse_refresh(content: &str) -> Option<(u64, Option<String>)> {
+    let rest = content.trim_left_matches(HTML_SPACE_CHARACTERS);
+    let (seconds, rest) = split_ascii_digits(rest);
+    if seconds.is_empty() && !rest.starts_with('.') {
+        return None;
+    }
+    let seconds = if seconds.is_empty() {
+        0
+    } else {
+        match u64::from_str(seconds) {
+            Ok(seconds) => seconds,
+            Err(_) => return None,
+        }
+    };
+
+    // Any fractional part of the delay is ignored.
+    let mut rest = rest.trim_left_matches(|c: char| c == '.' || c.is_digit(10))
+                       .trim_left_matches(HTML_SPACE_CHARACTERS);
+    if rest.starts_with(';') || rest.starts_with(',') {
+        rest = rest[1..].trim_left_matches(HTML_SPACE_CHARACTERS);
+    }
+
+    if rest.len() >= 3 && rest.as_bytes()[..3].eq_ignore_ascii_case(b"url") {
+        let after_url = rest[3..].trim_left_matches(HTML_SPACE_CHARACTERS);
+        if after_url.starts_with('=') {
+            rest = after_url[1..].trim_left_matches(HTML_SPACE_CHARACTERS);
+        }
+    }
+
+    let url = match rest.chars().next() {
+        Some(quote) if quote == '"' || quote == '\'' => {
+            let quoted = &rest[1..];
+            quoted.find(quote).map_or(quoted, |end| &quoted[..end])
+        },
+        _ => rest,
+    };
+    let url = url.trim_matches(HTML_SPACE_CHARACTERS);
+    Some((seconds, if url.is_empty() { None } else { Some(url.to_owned()) }))
+}
+
+/// Like `parse_refresh`, but returns the delay as a `Duration`.
+pub fn parse_refresh_duration(content: &str) -> Option<(Duration, Option<String>)> {
+    parse_refresh(content).map(|(seconds, url)| (Duration::from_secs(seconds), url))
+}
+
+/// The browsing context designated by a `target` attribute.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BrowsingContextName {
+    Blank,
+    Self_,
+    Parent,
+    Top,
+    Named(String),
+}
+
+/// Interprets `name` as a browsing context name or keyword, as given by the
+/// `target` attribute of `<a>`, `<base>` and `<form>`. The keywords are
+/// matched ASCII case-insensitively, and the empty string designates the
+/// current browsing context. Any other name, including those starting with
+/// `_`, is returned as written, to be looked up among the existing browsing
+/// contexts.
+///
+/// https://html.spec.whatwg.org/multipage/#the-rules-for-choosing-a-browsing-context-given-a-browsing-context-name
+pub fn normalize_browsing_context_name(name: &str) -> BrowsingContextName {
+    if name.is_empty() || name.eq_ignore_ascii_case("_self") {
+        BrowsingContextName::Self_
+    } else if name.eq_ignore_ascii_case("_blank") {
+        BrowsingContextName::Blank
+    } else if name.eq_ignore_ascii_case("_parent") {
+        BrowsingContextName::Parent
+    } else if name.eq_ignore_ascii_case("_top") {
+        BrowsingContextName::Top
+    } else {
+        BrowsingContextName::Named(name.to_owned())
+    }
+}
+
+/// Returns the number of hard lines in `s`: each LF terminates a line, and
+/// any text after the last LF forms one more. So `"a\nb\n"` has two lines,
+/// `"a\nb"` too, and the empty string has none.
+pub fn count_hard_lines(s: &str) -> usize {
+    let terminated = s.bytes().filter(|&b| b == b'\n').count();
+    if s.is_empty() || s.ends_with('\n') {
+        terminated
+    } else {
+        terminated + 1
+    }
+}
+
+/// Parses the value of an `accesskey` attribute, returning each of its
+/// space-separated tokens which consists of exactly one code point. Other
+/// tokens are ignored.
+///
+/// https://html.spec.whatwg.org/multipage/#the-accesskey-attribute
+pub fn parse_accesskeys(value: &str) -> Vec<char> {
+    split_html_space_chars(value).filter_map(|token| {
+        let mut chars = token.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    }).collect()
+}
+
+/// Parses an integer with an optional sign and an optional `0x`, `0o` or
+/// `0b` prefix (ASCII case-insensitive) selecting hexadecimal, octal or
+/// binary; it is decimal otherwise. Nothing else may surround it. This is
+/// only meant for internal debugging attributes: the HTML microsyntaxes are
+/// decimal-only.
+pub fn parse_integer_radix(input: &str) -> Option<i64> {
+    let (negative, unsigned) = match input.chars().next() {
+        Some('-') => (true, &input[1..]),
+        Some('+') => (false, &input[1..]),
+        _ => (false, input),
+    };
+    let (radix, digits) = if unsigned.len() >= 2 && unsigned.starts_with('0') {
+        match unsigned.as_bytes()[1] {
+            b'x' | b'X' => (16, &unsigned[2..]),
+            b'o' | b'O' => (8, &unsigned[2..]),
+            b'b' | b'B' => (2, &unsigned[2..]),
+            _ => (10, unsigned),
+        }
+    } else {
+        (10, unsigned)
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+
+    let magnitude = match u64::from_str_radix(digits, radix) {
+        Ok(magnitude) => magnitude,
+        Err(_) => return None,
+    };
+    if negative {
+        if magnitude <= i64::max_value() as u64 {
+            Some(-(magnitude as i64))
+        } else if magnitude == i64::max_value() as u64 + 1 {
+            Some(i64::min_value())
+        } else {
+            None
+        }
+    } else if magnitude <= i64::max_value() as u64 {
+        Some(magnitude as i64)
+    } else {
+        None
+    }
+}
+
+/// Strips and collapses the whitespace of `s`, as for an accessible name
+/// given by an `aria-label` or `title` attribute, returning `None` if nothing
+/// is left.
+///
+/// https://html.spec.whatwg.org/multipage/#strip-and-collapse-whitespace
+pub fn accessible_name_from_string(s: &str) -> Option<DOMString> {
+    let mut name = DOMString::from(s);
+    name.normalize_whitespace();
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
@@ -8,6 +8,7 @@
 
 extern crate alloc;
 extern crate app_units;
+extern crate cssparser;
 extern crate euclid;
 extern crate libc;
 extern crate util;
@@ -3,8 +3,41 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use app_units::Au;
-use util::str::LengthOrPercentageOrAuto;
-use util::str::{parse_length, search_index, split_html_space_chars, str_join};
+use cssparser::RGBA;
+use std::ascii::AsciiExt;
+use std::hash::{Hash, Hasher, SipHasher};
+use std::time::Duration;
+use util::str::{AreaShape, AsciiCaseInsensitive, BrowsingContextName, CollapsingStringBuilder,
+                DOMString, Direction, ImageCandidate, LengthOrPercentageOrAuto, LowercaseString,
+                MultiLength, NumberOrPercentage, SANDBOXED_AUTOMATIC_FEATURES,
+                SANDBOXED_AUXILIARY_NAVIGATION, SANDBOXED_FORMS, SANDBOXED_MODALS,
+                SANDBOXED_ORIENTATION_LOCK, SANDBOXED_ORIGIN, SANDBOXED_POINTER_LOCK,
+                SANDBOXED_PRESENTATION, SANDBOXED_SCRIPTS, SANDBOXED_TOP_LEVEL_NAVIGATION,
+                SANDBOX_PROPAGATES_TO_AUXILIARY_BROWSING_CONTEXTS, SandboxFlags, WhiteSpaceMode,
+                accessible_name_from_string, as_ascii, best_float_representation,
+                canonicalize_mime_essence, char_indices_utf16, contains_bidi_control,
+                content_disposition_filename, count_hard_lines, decode_fragment,
+                decode_named_char_refs, escape_html_attribute, escape_html_text, expand_tabs,
+                extract_charset_label, first_strong_direction, hash_ascii_lowercase,
+                header_is_nosniff, id_matches, is_ascii_lowercase, is_css_integer, is_token_str,
+                is_valid_form_field_name, is_valid_simple_color, is_well_formed_language_tag,
+                join_no_dup_sep, normalize_browsing_context_name, normalize_dom_newlines,
+                parse_accesskeys, parse_area_coords, parse_ascii_bool, parse_aspect_ratio,
+                parse_byte_ranges, parse_cache_control, parse_color_channel, parse_css_dimension,
+                parse_css_identifier, parse_css_integer, parse_css_number, parse_css_string,
+                parse_date_string, parse_datetime_local, parse_fixed_width_integer,
+                parse_float_list, parse_floating_point_number, parse_hex_color, parse_http_date,
+                parse_integer_allow_separators, parse_integer_list_into, parse_integer_radix,
+                parse_keyword_set, parse_length, parse_length_tagged, parse_month_string,
+                parse_multi_length_list, parse_number_or_percentage, parse_quoted_directive_list,
+                parse_quoted_string, parse_refresh, parse_refresh_duration, parse_sandbox,
+                parse_srcset, parse_time_string, parse_viewport, parse_week_string,
+                process_whitespace, resolve_color, resolve_two_digit_year, rgba_from_u32,
+                rgba_to_u32, rsplit_once_char, sanitize_simple_color, search_index,
+                serialize_header_list, slice_chars, slice_chars_checked, split_first_whitespace,
+                split_header_list, split_html_space_chars, split_mime_essence, split_once_char,
+                split_words, str_join, strip_prefix_ignore_ascii_case, trim_chars,
+                truncate_with_ellipsis, utf16_len};
 
 
 #[test]
@@ -63,3 +96,1446 @@ pub fn test_search_index() {
         assert_eq!(search_index(t.1, t.0.char_indices()), t.2);
     };
 }
+
+#[test]
+pub fn test_id_matches_standards_mode() {
+    assert!(id_matches("foo", "foo", false));
+    assert!(!id_matches("Foo", "foo", false));
+    assert!(!id_matches("foo", "FOO", false));
+}
+
+#[test]
+pub fn test_id_matches_quirks_mode() {
+    assert!(id_matches("foo", "foo", true));
+    assert!(id_matches("Foo", "foo", true));
+    assert!(id_matches("foo", "FOO", true));
+    assert!(!id_matches("foo", "bar", true));
+}
+
+#[test]
+pub fn test_domstring_repeat() {
+    let s = DOMString::from("ab");
+    assert_eq!(s.repeat(3), "ababab");
+    assert_eq!(s.repeat(0), "");
+}
+
+#[test]
+pub fn test_split_mime_essence() {
+    let (essence, parameters) = split_mime_essence("text/HTML; charset=utf-8");
+    assert_eq!(&*essence, "text/html");
+    assert_eq!(parameters, " charset=utf-8");
+
+    let (essence, parameters) = split_mime_essence("  Image/PNG  ");
+    assert_eq!(&*essence, "image/png");
+    assert_eq!(parameters, "");
+}
+
+#[test]
+pub fn test_is_token_str() {
+    assert!(is_token_str("Accept-Encoding"));
+    assert!(!is_token_str(""));
+    assert!(!is_token_str("with space"));
+}
+
+#[test]
+pub fn test_serialize_header_list() {
+    let items = vec!["Accept".to_owned(), "Accept-Encoding".to_owned(), "Origin".to_owned()];
+    assert_eq!(serialize_header_list(items), Ok("Accept, Accept-Encoding, Origin".to_owned()));
+
+    let items = vec!["GET".to_owned(), "not a token".to_owned()];
+    assert_eq!(serialize_header_list(items), Err(()));
+
+    assert_eq!(serialize_header_list(Vec::new()), Ok(String::new()));
+}
+
+#[test]
+pub fn test_parse_integer_allow_separators() {
+    assert_eq!(parse_integer_allow_separators("1_000"), Some(1000));
+    assert_eq!(parse_integer_allow_separators("-1_000_000"), Some(-1000000));
+    assert_eq!(parse_integer_allow_separators("  42"), Some(42));
+    assert_eq!(parse_integer_allow_separators("_1"), None);
+    assert_eq!(parse_integer_allow_separators("1_"), None);
+    assert_eq!(parse_integer_allow_separators("1__0"), None);
+    assert_eq!(parse_integer_allow_separators(""), None);
+}
+
+#[test]
+pub fn test_hash_ascii_lowercase() {
+    fn hash_of(s: &str) -> u64 {
+        let mut hasher = SipHasher::new();
+        hash_ascii_lowercase(s, &mut hasher);
+        hasher.finish()
+    }
+
+    assert_eq!(hash_of("Foo"), hash_of("foo"));
+    assert_eq!(hash_of("FOO"), hash_of("foo"));
+    assert!(hash_of("foo") != hash_of("bar"));
+}
+
+#[test]
+pub fn test_ascii_case_insensitive() {
+    fn hash_of(s: AsciiCaseInsensitive) -> u64 {
+        let mut hasher = SipHasher::new();
+        s.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    assert_eq!(AsciiCaseInsensitive("Foo"), AsciiCaseInsensitive("foo"));
+    assert!(AsciiCaseInsensitive("foo") != AsciiCaseInsensitive("bar"));
+    assert_eq!(hash_of(AsciiCaseInsensitive("Foo")), hash_of(AsciiCaseInsensitive("foo")));
+}
+
+#[test]
+pub fn test_parse_byte_ranges() {
+    assert_eq!(parse_byte_ranges("bytes=0-499"), Some(vec![(Some(0), Some(499))]));
+    assert_eq!(parse_byte_ranges("bytes=-500"), Some(vec![(None, Some(500))]));
+    assert_eq!(parse_byte_ranges("bytes=500-"), Some(vec![(Some(500), None)]));
+    assert_eq!(parse_byte_ranges("bytes=0-0, 500-999,-1"),
+               Some(vec![(Some(0), Some(0)), (Some(500), Some(999)), (None, Some(1))]));
+    assert_eq!(parse_byte_ranges("Bytes=1-2"), Some(vec![(Some(1), Some(2))]));
+
+    assert_eq!(parse_byte_ranges("items=0-5"), None);
+    assert_eq!(parse_byte_ranges("bytes=-"), None);
+    assert_eq!(parse_byte_ranges("bytes=5-1"), None);
+    assert_eq!(parse_byte_ranges("bytes=a-b"), None);
+    assert_eq!(parse_byte_ranges("bytes="), None);
+    assert_eq!(parse_byte_ranges("0-499"), None);
+}
+
+#[test]
+pub fn test_collapsing_string_builder() {
+    let mut builder = CollapsingStringBuilder::new();
+    builder.push_str("a  ");
+    builder.push_str(" b");
+    assert_eq!(builder.finish(), "a b");
+
+    let mut builder = CollapsingStringBuilder::new();
+    builder.push_str("\t\nfoo\x0c\rbar ");
+    assert_eq!(builder.finish(), " foo bar ");
+
+    assert_eq!(CollapsingStringBuilder::new().finish(), "");
+}
+
+#[test]
+pub fn test_parse_quoted_string() {
+    assert_eq!(parse_quoted_string("\"a\\\"b\" rest"), Some(("a\"b".to_owned(), " rest")));
+    assert_eq!(parse_quoted_string("\"a\\\\b\""), Some(("a\\b".to_owned(), "")));
+    assert_eq!(parse_quoted_string("\"\";q=1"), Some((String::new(), ";q=1")));
+    assert_eq!(parse_quoted_string("\"unterminated"), None);
+    assert_eq!(parse_quoted_string("\"trailing backslash\\"), None);
+    assert_eq!(parse_quoted_string("no quote"), None);
+}
+
+#[test]
+pub fn test_length_or_percentage_or_auto_resolve() {
+    let containing = Au::from_px(200);
+    assert_eq!(LengthOrPercentageOrAuto::Auto.resolve(containing), None);
+    assert_eq!(LengthOrPercentageOrAuto::Percentage(0.25).resolve(containing), Some(Au::from_px(50)));
+    assert_eq!(LengthOrPercentageOrAuto::Length(Au::from_px(12)).resolve(containing),
+               Some(Au::from_px(12)));
+}
+
+#[test]
+pub fn test_domstring_normalize_whitespace() {
+    fn check(input: &str, expected: &str) {
+        let mut s = DOMString::from(input);
+        s.normalize_whitespace();
+        assert_eq!(s, expected);
+    }
+
+    check("  a \n b  ", "a b");
+    check("a", "a");
+    check("", "");
+    check(" \t\r\n\x0c", "");
+    check("\u{e9}\t\t\u{1f600}", "\u{e9} \u{1f600}");
+}
+
+#[test]
+pub fn test_parse_multi_length_list() {
+    assert_eq!(parse_multi_length_list("*, 100, 2*, 25%"),
+               vec![MultiLength::Relative(0.),
+                    MultiLength::Absolute(Au::from_px(100)),
+                    MultiLength::Relative(2.),
+                    MultiLength::Percentage(0.25)]);
+    assert_eq!(parse_multi_length_list("1.5*,50 %,"),
+               vec![MultiLength::Relative(1.5), MultiLength::Percentage(0.5)]);
+    assert_eq!(parse_multi_length_list("10,,20"),
+               vec![MultiLength::Absolute(Au::from_px(10)),
+                    MultiLength::Relative(0.),
+                    MultiLength::Absolute(Au::from_px(20))]);
+    assert_eq!(parse_multi_length_list("99999999999999999999*, 99999999999999999999.5%"),
+               vec![MultiLength::Relative(1e20), MultiLength::Percentage(1e18)]);
+}
+
+#[test]
+pub fn test_lowercase_string_contains() {
+    let s = LowercaseString::new("Text/HTML");
+    assert!(s.contains("html"));
+    assert!(!s.contains("HTML"));
+    assert!(!s.contains("xml"));
+}
+
+#[test]
+pub fn test_slice_chars() {
+    assert_eq!(slice_chars("hello", 0, 0), "");
+    assert_eq!(slice_chars("hello", 1, 4), "ell");
+    assert_eq!(slice_chars("hello", 0, 5), "hello");
+    assert_eq!(slice_chars("hello", 5, 5), "");
+
+    // Compare both the ASCII fast path and the general path, which is taken
+    // whenever a non-ASCII char precedes `end`, against slicing by
+    // `char_indices`.
+    fn reference(s: &str, begin: usize, end: usize) -> &str {
+        let mut boundaries: Vec<usize> = s.char_indices().map(|(index, _)| index).collect();
+        boundaries.push(s.len());
+        &s[boundaries[begin]..boundaries[end]]
+    }
+    for s in &["hello", "h\u{e9}llo", "ab\u{1f600}", "\u{1f600}\u{e9}x\u{65e5}y", "abc\u{e9}"] {
+        let length = s.chars().count();
+        for begin in 0..length + 1 {
+            for end in begin..length + 1 {
+                assert_eq!(slice_chars(s, begin, end), reference(s, begin, end));
+            }
+        }
+    }
+    assert_eq!(slice_chars("h\u{e9}llo", 1, 4), "\u{e9}ll");
+    assert_eq!(slice_chars("ab\u{1f600}", 2, 3), "\u{1f600}");
+}
+
+#[test]
+#[should_panic]
+pub fn test_slice_chars_end_out_of_range() {
+    slice_chars("abc", 0, 4);
+}
+
+#[test]
+pub fn test_is_well_formed_language_tag() {
+    assert!(is_well_formed_language_tag("en"));
+    assert!(is_well_formed_language_tag("en-US"));
+    assert!(is_well_formed_language_tag("zh-Hant-CN"));
+    assert!(is_well_formed_language_tag("zh-yue-HK"));
+    assert!(is_well_formed_language_tag("es-419"));
+    assert!(is_well_formed_language_tag("de-CH-1901"));
+    assert!(is_well_formed_language_tag("sl-rozaj-biske"));
+    assert!(is_well_formed_language_tag("en-US-u-islamcal"));
+    assert!(is_well_formed_language_tag("en-x-private"));
+    assert!(is_well_formed_language_tag("x-whatever"));
+    assert!(is_well_formed_language_tag("i-klingon"));
+    assert!(is_well_formed_language_tag("I-KLINGON"));
+
+    assert!(!is_well_formed_language_tag(""));
+    assert!(!is_well_formed_language_tag("en_US"));
+    assert!(!is_well_formed_language_tag("en-"));
+    assert!(!is_well_formed_language_tag("e"));
+    assert!(!is_well_formed_language_tag("123"));
+    assert!(!is_well_formed_language_tag("en-a"));
+    assert!(!is_well_formed_language_tag("en-x"));
+    assert!(!is_well_formed_language_tag("en-US-US"));
+    assert!(!is_well_formed_language_tag("toolongsubtag"));
+}
+
+#[test]
+pub fn test_parse_fixed_width_integer() {
+    assert_eq!(parse_fixed_width_integer("2024", 4), Some(2024));
+    assert_eq!(parse_fixed_width_integer("0007", 4), Some(7));
+    assert_eq!(parse_fixed_width_integer("24", 4), None);
+    assert_eq!(parse_fixed_width_integer("20245", 4), None);
+    assert_eq!(parse_fixed_width_integer("20a4", 4), None);
+    assert_eq!(parse_fixed_width_integer("+024", 4), None);
+    assert_eq!(parse_fixed_width_integer("", 0), None);
+    assert_eq!(parse_fixed_width_integer("99999999999", 11), None);
+}
+
+#[test]
+pub fn test_escape_html_text() {
+    assert_eq!(escape_html_text("plain"), "plain");
+    assert_eq!(escape_html_text("a & b"), "a &amp; b");
+    assert_eq!(escape_html_text("<p>"), "&lt;p&gt;");
+    assert_eq!(escape_html_text("\"quoted\"\u{a0}"), "\"quoted\"&nbsp;");
+}
+
+#[test]
+pub fn test_escape_html_attribute() {
+    assert_eq!(escape_html_attribute("plain"), "plain");
+    assert_eq!(escape_html_attribute("a & b"), "a &amp; b");
+    assert_eq!(escape_html_attribute("<p>"), "&lt;p&gt;");
+    assert_eq!(escape_html_attribute("\"quoted\""), "&quot;quoted&quot;");
+    assert_eq!(escape_html_attribute("a\u{a0}b"), "a&nbsp;b");
+}
+
+#[test]
+pub fn test_domstring_insert_str() {
+    let mut s = DOMString::from("bd");
+    s.insert_str(0, "a");
+    assert_eq!(s, "abd");
+    s.insert_str(2, "c");
+    assert_eq!(s, "abcd");
+    s.insert_str(4, "\u{e9}");
+    assert_eq!(s, "abcd\u{e9}");
+}
+
+#[test]
+#[should_panic]
+pub fn test_domstring_insert_str_not_char_boundary() {
+    let mut s = DOMString::from("\u{e9}");
+    s.insert_str(1, "a");
+}
+
+#[test]
+pub fn test_domstring_remove() {
+    let mut s = DOMString::from("a\u{e9}b");
+    assert_eq!(s.remove(1), '\u{e9}');
+    assert_eq!(s, "ab");
+    assert_eq!(s.remove(0), 'a');
+    assert_eq!(s, "b");
+}
+
+#[test]
+pub fn test_extract_charset_label() {
+    assert_eq!(extract_charset_label("text/html; charset=UTF-8"), Some("UTF-8".to_owned()));
+    assert_eq!(extract_charset_label("charset='utf-8'"), Some("utf-8".to_owned()));
+    assert_eq!(extract_charset_label("text/html; CHARSET = \"koi8-r\"; x"), Some("koi8-r".to_owned()));
+    assert_eq!(extract_charset_label("charset=latin1 foo"), Some("latin1".to_owned()));
+    assert_eq!(extract_charset_label("charsetX; charset=big5"), Some("big5".to_owned()));
+    assert_eq!(extract_charset_label("charset=\"unterminated"), None);
+    assert_eq!(extract_charset_label("charset="), None);
+    assert_eq!(extract_charset_label("text/html"), None);
+}
+
+#[test]
+pub fn test_domstring_lines() {
+    let s = DOMString::from("one\r\ntwo\r\n\r\nfour");
+    assert_eq!(s.lines().collect::<Vec<_>>(), vec!["one", "two", "", "four"]);
+    assert_eq!(s.lines().collect::<Vec<_>>(), "one\r\ntwo\r\n\r\nfour".lines().collect::<Vec<_>>());
+    assert_eq!(s.line_count(), 4);
+
+    assert_eq!(DOMString::from("trailing\r\n").line_count(), 1);
+    assert_eq!(DOMString::new().line_count(), 0);
+}
+
+#[test]
+pub fn test_parse_aspect_ratio() {
+    assert_eq!(parse_aspect_ratio("16", "9"), Some(16. / 9.));
+    assert_eq!(parse_aspect_ratio(" 300px", "150"), Some(2.));
+    assert_eq!(parse_aspect_ratio("16", "0"), None);
+    assert_eq!(parse_aspect_ratio("-16", "9"), None);
+    assert_eq!(parse_aspect_ratio("auto", "9"), None);
+}
+
+#[test]
+pub fn test_split_once_char() {
+    assert_eq!(split_once_char("a=b=c", '='), Some(("a", "b=c")));
+    assert_eq!(split_once_char("=b", '='), Some(("", "b")));
+    assert_eq!(split_once_char("a\u{e9}b", '\u{e9}'), Some(("a", "b")));
+    assert_eq!(split_once_char("abc", '='), None);
+}
+
+#[test]
+pub fn test_parse_hex_color() {
+    fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> RGBA {
+        RGBA {
+            red: red as f32 / 255.0,
+            green: green as f32 / 255.0,
+            blue: blue as f32 / 255.0,
+            alpha: alpha as f32 / 255.0,
+        }
+    }
+
+    assert_eq!(parse_hex_color("#f0a"), Some(rgba(0xff, 0x00, 0xaa, 0xff)));
+    assert_eq!(parse_hex_color("#f0a8"), Some(rgba(0xff, 0x00, 0xaa, 0x88)));
+    assert_eq!(parse_hex_color("#FF00AA"), Some(rgba(0xff, 0x00, 0xaa, 0xff)));
+    assert_eq!(parse_hex_color("#ff00aa80"), Some(rgba(0xff, 0x00, 0xaa, 0x80)));
+
+    assert_eq!(parse_hex_color("#gg0000"), None);
+    assert_eq!(parse_hex_color("ff00aa"), None);
+    assert_eq!(parse_hex_color("#ff00a"), None);
+    assert_eq!(parse_hex_color("#"), None);
+    assert_eq!(parse_hex_color(" #fff"), None);
+}
+
+#[test]
+pub fn test_utf16_len() {
+    assert_eq!(utf16_len(""), 0);
+    assert_eq!(utf16_len("abc"), 3);
+    assert_eq!(utf16_len("\u{e9}\u{20ac}"), 2);
+    assert_eq!(utf16_len("a\u{1f600}b"), 4);
+    assert_eq!(utf16_len("\u{1f600}\u{10ffff}"), 4);
+
+    assert_eq!(DOMString::from("a\u{1f600}").utf16_len(), 3);
+
+    // The cached ASCII-ness is invalidated by mutation.
+    let mut s = DOMString::from("abc");
+    assert_eq!(s.utf16_len(), 3);
+    s.push_str("\u{1f600}");
+    assert_eq!(s.utf16_len(), 5);
+}
+
+#[test]
+pub fn test_parse_srcset() {
+    fn candidate(url: &str, density: Option<f64>, width: Option<u32>) -> ImageCandidate {
+        ImageCandidate {
+            url: url.to_owned(),
+            density: density,
+            width: width,
+        }
+    }
+
+    assert_eq!(parse_srcset("a.jpg 1x, b.jpg 2x, c.jpg 600w"),
+               vec![candidate("a.jpg", Some(1.), None),
+                    candidate("b.jpg", Some(2.), None),
+                    candidate("c.jpg", None, Some(600))]);
+    assert_eq!(parse_srcset("a.jpg"), vec![candidate("a.jpg", None, None)]);
+    assert_eq!(parse_srcset("a.jpg, b.jpg 1.5x"),
+               vec![candidate("a.jpg", None, None), candidate("b.jpg", Some(1.5), None)]);
+    assert_eq!(parse_srcset("data:image/png;base64,AAAA 2x"),
+               vec![candidate("data:image/png;base64,AAAA", Some(2.), None)]);
+    assert_eq!(parse_srcset("a.jpg 100w 50h"), vec![candidate("a.jpg", None, Some(100))]);
+    assert_eq!(parse_srcset("  ,, a.jpg,,, "), vec![candidate("a.jpg", None, None)]);
+    assert_eq!(parse_srcset(""), vec![]);
+
+    // Candidates with invalid descriptors are dropped.
+    assert_eq!(parse_srcset("a.jpg 0w, b.jpg 1x 2x, c.jpg -1x, d.jpg 10q, e.jpg 50h, f.jpg 2x, g.jpg 2\u{e9}"),
+               vec![candidate("f.jpg", Some(2.), None)]);
+}
+
+#[test]
+pub fn test_domstring_is_valid_email() {
+    assert!(DOMString::from("a@b.co").is_valid_email());
+    assert!(DOMString::from("first.last+tag@example-host.org").is_valid_email());
+    assert!(DOMString::from("a@localhost").is_valid_email());
+
+    assert!(!DOMString::from("a@@b").is_valid_email());
+    assert!(!DOMString::from("@b.co").is_valid_email());
+    assert!(!DOMString::from("a@").is_valid_email());
+    assert!(!DOMString::from("a@b..co").is_valid_email());
+    assert!(!DOMString::from("a@-b.co").is_valid_email());
+    assert!(!DOMString::from("a b@c.co").is_valid_email());
+    assert!(!DOMString::from("ab.co").is_valid_email());
+}
+
+#[test]
+pub fn test_domstring_is_valid_email_list() {
+    assert!(DOMString::from("a@b, c@d").is_valid_email_list());
+    assert!(DOMString::from("a@b").is_valid_email_list());
+    assert!(DOMString::from("").is_valid_email_list());
+
+    assert!(!DOMString::from("a@b, c").is_valid_email_list());
+    assert!(!DOMString::from("a@b,").is_valid_email_list());
+}
+
+#[test]
+pub fn test_expand_tabs() {
+    assert_eq!(expand_tabs("a\tb", 4), "a   b");
+    assert_eq!(expand_tabs("ab\tc", 4), "ab  c");
+    assert_eq!(expand_tabs("abcd\te", 4), "abcd    e");
+    assert_eq!(expand_tabs("\t\t", 4), "        ");
+    assert_eq!(expand_tabs("abc\n\tx", 4), "abc\n    x");
+    assert_eq!(expand_tabs("\u{e9}\tx", 2), "\u{e9} x");
+    assert_eq!(expand_tabs("a\tb", 0), "ab");
+}
+
+#[test]
+pub fn test_resolve_color() {
+    let current_color = RGBA { red: 0.5, green: 0.25, blue: 0.75, alpha: 1. };
+    assert_eq!(resolve_color("currentColor", current_color), Some(current_color));
+    assert_eq!(resolve_color(" CURRENTCOLOR ", current_color), Some(current_color));
+    assert_eq!(resolve_color("transparent", current_color),
+               Some(RGBA { red: 0., green: 0., blue: 0., alpha: 0. }));
+    assert_eq!(resolve_color("red", current_color),
+               Some(RGBA { red: 1., green: 0., blue: 0., alpha: 1. }));
+    assert_eq!(resolve_color("not a color", current_color), None);
+}
+
+#[test]
+pub fn test_normalize_dom_newlines() {
+    assert_eq!(normalize_dom_newlines("a\r\nb\rc\nd"), "a\nb\nc\nd");
+    assert_eq!(normalize_dom_newlines("\r\r\n\n"), "\n\n\n");
+    assert_eq!(normalize_dom_newlines("no newlines"), "no newlines");
+
+    let mut s = DOMString::from("a\r\nb\rc\nd");
+    s.normalize_newlines();
+    assert_eq!(s, "a\nb\nc\nd");
+}
+
+#[test]
+pub fn test_domstring_is_ascii() {
+    let mut s = DOMString::from("abc");
+    assert!(s.is_ascii());
+    assert!(s.is_ascii());
+
+    s.push_str("\u{e9}");
+    assert!(!s.is_ascii());
+
+    s.remove(3);
+    assert!(s.is_ascii());
+
+    s.extend("\u{1f600}".chars());
+    assert!(!s.is_ascii());
+
+    s.clear();
+    assert!(s.is_ascii());
+
+    s.insert_str(0, "\u{e9}");
+    assert!(!s.is_ascii());
+
+    // The cache takes no part in comparisons.
+    let mut cached = DOMString::from("abc");
+    assert!(cached.is_ascii());
+    assert_eq!(cached, DOMString::from("abc"));
+    cached.push_str("d");
+    assert_eq!(cached, "abcd");
+}
+
+#[test]
+pub fn test_parse_http_date() {
+    assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT", 2016), Some(784111777));
+    assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT", 2016), Some(784111777));
+    assert_eq!(parse_http_date("Sun Nov  6 08:49:37 1994", 2016), Some(784111777));
+
+    assert_eq!(parse_http_date("Thu, 01 Jan 1970 00:00:00 GMT", 2016), Some(0));
+    assert_eq!(parse_http_date("Thu, 29 Feb 2024 12:00:00 GMT", 2016), Some(1709208000));
+    assert_eq!(parse_http_date("Wednesday, 01-Jan-20 00:00:00 GMT", 2016), Some(1577836800));
+    assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT", 2050), Some(3939871777));
+
+    assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 UTC", 2016), None);
+    assert_eq!(parse_http_date("Sun, 31 Nov 1994 08:49:37 GMT", 2016), None);
+    assert_eq!(parse_http_date("Sun, 06 Nov 1994 24:00:00 GMT", 2016), None);
+    assert_eq!(parse_http_date("Sun, 06 Foo 1994 08:49:37 GMT", 2016), None);
+    assert_eq!(parse_http_date("Sunday, 06 Nov 1994 08:49:37 GMT", 2016), None);
+    assert_eq!(parse_http_date("not a date", 2016), None);
+    assert_eq!(parse_http_date("", 2016), None);
+}
+
+#[test]
+pub fn test_domstring_count_char() {
+    let s = DOMString::from("a,b,,c");
+    assert_eq!(s.count_char(','), 3);
+    assert_eq!(s.count_char('@'), 0);
+
+    let s = DOMString::from("\u{e9}t\u{e9}, caf\u{e9}");
+    assert_eq!(s.count_char('\u{e9}'), 3);
+    assert_eq!(s.count_char(','), 1);
+}
+
+#[test]
+pub fn test_parse_color_channel() {
+    assert_eq!(parse_color_channel("255"), Some(255));
+    assert_eq!(parse_color_channel("0"), Some(0));
+    assert_eq!(parse_color_channel("300"), Some(255));
+    assert_eq!(parse_color_channel("-20"), Some(0));
+    assert_eq!(parse_color_channel("50%"), Some(128));
+    assert_eq!(parse_color_channel("100%"), Some(255));
+    assert_eq!(parse_color_channel("12.5%"), Some(32));
+    assert_eq!(parse_color_channel("150%"), Some(255));
+
+    assert_eq!(parse_color_channel("abc"), None);
+    assert_eq!(parse_color_channel(""), None);
+    assert_eq!(parse_color_channel("%"), None);
+    assert_eq!(parse_color_channel("1.5"), None);
+    assert_eq!(parse_color_channel("--1"), None);
+    assert_eq!(parse_color_channel("inf"), None);
+}
+
+#[test]
+pub fn test_domstring_needs_html_escaping() {
+    assert!(!DOMString::from("clean text").needs_html_escaping(false));
+    assert!(!DOMString::from("clean text").needs_html_escaping(true));
+    assert!(DOMString::from("a & b").needs_html_escaping(false));
+    assert!(DOMString::from("a & b").needs_html_escaping(true));
+    assert!(DOMString::from("<b>").needs_html_escaping(false));
+    assert!(DOMString::from("\"a\u{a0}b\"").needs_html_escaping(false));
+    assert!(!DOMString::from("\"a\"").needs_html_escaping(false));
+    assert!(DOMString::from("\"a\"").needs_html_escaping(true));
+    assert!(DOMString::from("a\u{a0}b").needs_html_escaping(true));
+}
+
+#[test]
+pub fn test_parse_length_tagged() {
+    assert_eq!(parse_length_tagged("10px"), (LengthOrPercentageOrAuto::Length(Au::from_px(10)), true));
+    assert_eq!(parse_length_tagged("10PX"), (LengthOrPercentageOrAuto::Length(Au::from_px(10)), true));
+    assert_eq!(parse_length_tagged("10"), (LengthOrPercentageOrAuto::Length(Au::from_px(10)), false));
+    assert_eq!(parse_length_tagged("10 px"), (LengthOrPercentageOrAuto::Length(Au::from_px(10)), false));
+    assert_eq!(parse_length_tagged("10em"), (LengthOrPercentageOrAuto::Length(Au::from_px(10)), false));
+    assert_eq!(parse_length_tagged("50%"), (LengthOrPercentageOrAuto::Percentage(0.5), false));
+    assert_eq!(parse_length_tagged("px"), (LengthOrPercentageOrAuto::Auto, false));
+    assert_eq!(parse_length_tagged("10p\u{e9}"), (LengthOrPercentageOrAuto::Length(Au::from_px(10)), false));
+}
+
+#[test]
+pub fn test_join_no_dup_sep() {
+    assert_eq!(join_no_dup_sep(&["a/", "/b", "c"], '/'), "a/b/c");
+    assert_eq!(join_no_dup_sep(&["/a//", "//b/"], '/'), "/a/b/");
+    assert_eq!(join_no_dup_sep(&["a", "", "b"], '/'), "a/b");
+    assert_eq!(join_no_dup_sep(&["a"], '/'), "a");
+    assert_eq!(join_no_dup_sep(&[], '/'), "");
+}
+
+#[test]
+pub fn test_domstring_eq_bytes() {
+    let s = DOMString::from("GIF89a");
+    assert!(s == b"GIF89a"[..]);
+    assert!(s != b"GIF87a"[..]);
+    assert!(s != b"GIF"[..]);
+    assert!(DOMString::from("\u{e9}") == [0xc3, 0xa9][..]);
+}
+
+#[test]
+pub fn test_parse_ascii_bool() {
+    assert_eq!(parse_ascii_bool("TRUE"), Some(true));
+    assert_eq!(parse_ascii_bool("true"), Some(true));
+    assert_eq!(parse_ascii_bool("false"), Some(false));
+    assert_eq!(parse_ascii_bool("False"), Some(false));
+    assert_eq!(parse_ascii_bool("yes"), None);
+    assert_eq!(parse_ascii_bool(""), None);
+    assert_eq!(parse_ascii_bool(" true"), None);
+}
+
+#[test]
+pub fn test_parse_css_identifier() {
+    assert_eq!(parse_css_identifier("foo-bar rest"), Some(("foo-bar".to_owned(), " rest")));
+    assert_eq!(parse_css_identifier("\\31 23"), Some(("123".to_owned(), "")));
+    assert_eq!(parse_css_identifier("a\\,b:c"), Some(("a,b".to_owned(), ":c")));
+    assert_eq!(parse_css_identifier("--custom"), Some(("--custom".to_owned(), "")));
+    assert_eq!(parse_css_identifier("-moz-box"), Some(("-moz-box".to_owned(), "")));
+    assert_eq!(parse_css_identifier("_\u{e9}t\u{e9}2{"), Some(("_\u{e9}t\u{e9}2".to_owned(), "{")));
+    assert_eq!(parse_css_identifier("\\41\\42 C"), Some(("ABC".to_owned(), "")));
+    assert_eq!(parse_css_identifier("\\0 x"), Some(("\u{fffd}x".to_owned(), "")));
+    assert_eq!(parse_css_identifier("\\110000"), Some(("\u{fffd}".to_owned(), "")));
+
+    assert_eq!(parse_css_identifier("1abc"), None);
+    assert_eq!(parse_css_identifier("-1"), None);
+    assert_eq!(parse_css_identifier("\\\nfoo"), None);
+    assert_eq!(parse_css_identifier(" foo"), None);
+    assert_eq!(parse_css_identifier(""), None);
+}
+
+#[test]
+pub fn test_trim_chars() {
+    assert_eq!(trim_chars(",;a, b;;,", &[',', ';']), "a, b");
+    assert_eq!(trim_chars(",;,", &[',', ';']), "");
+    assert_eq!(trim_chars("abc", &[',', ';']), "abc");
+    assert_eq!(trim_chars(" abc ", &[]), " abc ");
+}
+
+#[test]
+pub fn test_domstring_from_iter() {
+    let s: DOMString = "abc".chars().rev().collect();
+    assert_eq!(s, "cba");
+
+    let s = vec!['\u{e9}', 't', '\u{e9}'].into_iter().collect::<DOMString>();
+    assert_eq!(s, "\u{e9}t\u{e9}");
+
+    let s: DOMString = "".chars().collect();
+    assert_eq!(s, "");
+}
+
+#[test]
+pub fn test_parse_floating_point_number() {
+    assert_eq!(parse_floating_point_number("1"), Some(1.));
+    assert_eq!(parse_floating_point_number("  -1.5e2"), Some(-150.));
+    assert_eq!(parse_floating_point_number("+.5"), Some(0.5));
+    assert_eq!(parse_floating_point_number("1.e5"), Some(1.));
+    assert_eq!(parse_floating_point_number("2e"), Some(2.));
+    assert_eq!(parse_floating_point_number("12px"), Some(12.));
+    assert_eq!(parse_floating_point_number("-0"), Some(0.));
+
+    assert_eq!(parse_floating_point_number(""), None);
+    assert_eq!(parse_floating_point_number("-"), None);
+    assert_eq!(parse_floating_point_number("."), None);
+    assert_eq!(parse_floating_point_number("abc"), None);
+    assert_eq!(parse_floating_point_number("1e400"), None);
+}
+
+#[test]
+pub fn test_parse_area_coords() {
+    assert_eq!(parse_area_coords(AreaShape::Rect, "0, 0, 100, 50"), Some(vec![0., 0., 100., 50.]));
+    assert_eq!(parse_area_coords(AreaShape::Rect, "0,0,100"), None);
+    assert_eq!(parse_area_coords(AreaShape::Circle, "50;50 25"), Some(vec![50., 50., 25.]));
+    assert_eq!(parse_area_coords(AreaShape::Circle, "50,50,25,1"), None);
+    assert_eq!(parse_area_coords(AreaShape::Poly, "0,0 10,0 10,10"),
+               Some(vec![0., 0., 10., 0., 10., 10.]));
+    assert_eq!(parse_area_coords(AreaShape::Poly, "0,0,10,0,10"), None);
+    assert_eq!(parse_area_coords(AreaShape::Poly, "0,0,10,0"), None);
+    assert_eq!(parse_area_coords(AreaShape::Default, ""), Some(vec![]));
+
+    // Unparseable numbers are treated as zero.
+    assert_eq!(parse_area_coords(AreaShape::Circle, "x, 2.5px, 3"), Some(vec![0., 2.5, 3.]));
+}
+
+#[test]
+pub fn test_lowercase_string_display() {
+    assert_eq!(format!("type: {}", LowercaseString::new("Text/HTML")), "type: text/html");
+}
+
+#[test]
+pub fn test_parse_keyword_set() {
+    static KNOWN: &'static [&'static str] = &["allow-forms", "allow-scripts", "allow-popups"];
+
+    let keywords = parse_keyword_set("allow-scripts ALLOW-FORMS unknown allow-scripts", KNOWN);
+    assert_eq!(keywords.len(), 2);
+    assert!(keywords.contains("allow-scripts"));
+    assert!(keywords.contains("allow-forms"));
+
+    assert!(parse_keyword_set("", KNOWN).is_empty());
+    assert!(parse_keyword_set("  unknown\t", KNOWN).is_empty());
+}
+
+#[test]
+pub fn test_slice_chars_checked() {
+    assert_eq!(slice_chars_checked("hello", 1, 4), Some("ell"));
+    assert_eq!(slice_chars_checked("h\u{e9}llo", 1, 5), Some("\u{e9}llo"));
+    assert_eq!(slice_chars_checked("hello", 0, 6), None);
+    assert_eq!(slice_chars_checked("hello", 6, 6), None);
+    assert_eq!(slice_chars_checked("hello", 3, 2), None);
+}
+
+#[test]
+pub fn test_domstring_char_slice() {
+    let s = DOMString::from("a\u{e9}\u{1f600}b");
+    assert_eq!(s.char_slice(0, 4), Some("a\u{e9}\u{1f600}b"));
+    assert_eq!(s.char_slice(1, 3), Some("\u{e9}\u{1f600}"));
+    assert_eq!(s.char_slice(4, 4), Some(""));
+    assert_eq!(s.char_slice(2, 5), None);
+    assert_eq!(s.char_slice(5, 6), None);
+    assert_eq!(s.char_slice(3, 1), None);
+
+    let mut s = DOMString::from("abcd");
+    assert_eq!(s.char_slice(1, 3), Some("bc"));
+    assert_eq!(s.char_slice(4, 4), Some(""));
+    assert_eq!(s.char_slice(3, 5), None);
+    assert_eq!(s.char_slice(3, 1), None);
+    s.insert_str(0, "\u{e9}");
+    assert_eq!(s.char_slice(1, 3), Some("ab"));
+}
+
+#[test]
+pub fn test_parse_number_or_percentage() {
+    assert_eq!(parse_number_or_percentage("50%"), Some(NumberOrPercentage::Percentage(0.5)));
+    assert_eq!(parse_number_or_percentage(" 12.5% "), Some(NumberOrPercentage::Percentage(0.125)));
+    assert_eq!(parse_number_or_percentage("0.5"), Some(NumberOrPercentage::Number(0.5)));
+    assert_eq!(parse_number_or_percentage("-3"), Some(NumberOrPercentage::Number(-3.)));
+    assert_eq!(parse_number_or_percentage("%"), None);
+    assert_eq!(parse_number_or_percentage("abc"), None);
+    assert_eq!(parse_number_or_percentage("50%abc"), Some(NumberOrPercentage::Percentage(0.5)));
+    assert_eq!(parse_number_or_percentage("5x%"), Some(NumberOrPercentage::Number(5.)));
+    assert_eq!(parse_number_or_percentage("5 %"), Some(NumberOrPercentage::Number(5.)));
+}
+
+#[test]
+pub fn test_first_strong_direction() {
+    assert_eq!(first_strong_direction("hello"), Some(Direction::Ltr));
+    assert_eq!(first_strong_direction("123 hello \u{645}\u{631}\u{62d}\u{628}\u{627}"), Some(Direction::Ltr));
+    assert_eq!(first_strong_direction("\u{645}\u{631}\u{62d}\u{628}\u{627} hello"), Some(Direction::Rtl));
+    assert_eq!(first_strong_direction("(1) \u{5e9}\u{5dc}\u{5d5}\u{5dd}"), Some(Direction::Rtl));
+    assert_eq!(first_strong_direction("123 !?"), None);
+    assert_eq!(first_strong_direction(""), None);
+}
+
+#[test]
+pub fn test_parse_viewport() {
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|&(key, value)| (key.to_owned(), value.to_owned())).collect()
+    }
+
+    assert_eq!(parse_viewport("width=device-width, initial-scale=1"),
+               pairs(&[("width", "device-width"), ("initial-scale", "1")]));
+    assert_eq!(parse_viewport("width = 500 ;; , user-scalable=no;initial-scale"),
+               pairs(&[("width", "500"), ("user-scalable", "no"), ("initial-scale", "")]));
+    assert_eq!(parse_viewport("  width=device-width height=device-height  "),
+               pairs(&[("width", "device-width"), ("height", "device-height")]));
+    assert_eq!(parse_viewport("=,;"), pairs(&[]));
+    assert_eq!(parse_viewport(""), pairs(&[]));
+}
+
+#[test]
+pub fn test_domstring_ascii_transform() {
+    let mut s = DOMString::from("HeLLo World");
+    s.ascii_transform(|b| b.to_ascii_lowercase());
+    assert_eq!(s, "hello world");
+    assert!(s.is_ascii());
+
+    let mut s = DOMString::from("\u{c0}bC \u{df}");
+    s.ascii_transform(|b| b.to_ascii_lowercase());
+    assert_eq!(s, "\u{c0}bc \u{df}");
+
+    let mut s = DOMString::from("bab");
+    s.ascii_transform(|b| if b == b'a' { 0xE9 } else { b });
+    assert_eq!(s, "b\u{e9}b");
+    assert!(!s.is_ascii());
+
+    let mut s = DOMString::new();
+    s.ascii_transform(|b| b.to_ascii_uppercase());
+    assert_eq!(s, "");
+}
+
+#[test]
+pub fn test_parse_sandbox() {
+    assert_eq!(parse_sandbox(""), SandboxFlags::all());
+
+    let flags = parse_sandbox("allow-scripts");
+    assert!(!flags.contains(SANDBOXED_SCRIPTS));
+    assert!(!flags.contains(SANDBOXED_AUTOMATIC_FEATURES));
+    assert!(flags.contains(SANDBOXED_ORIGIN));
+    assert!(flags.contains(SANDBOXED_FORMS));
+
+    assert_eq!(parse_sandbox(" ALLOW-SAME-ORIGIN\tallow-forms allow-everything "),
+               SandboxFlags::all() - SANDBOXED_ORIGIN - SANDBOXED_FORMS);
+    assert_eq!(parse_sandbox("allow-nothing bogus"), SandboxFlags::all());
+
+    let keywords = [
+        ("allow-forms", SANDBOXED_FORMS),
+        ("allow-modals", SANDBOXED_MODALS),
+        ("allow-orientation-lock", SANDBOXED_ORIENTATION_LOCK),
+        ("allow-pointer-lock", SANDBOXED_POINTER_LOCK),
+        ("allow-popups", SANDBOXED_AUXILIARY_NAVIGATION),
+        ("allow-popups-to-escape-sandbox", SANDBOX_PROPAGATES_TO_AUXILIARY_BROWSING_CONTEXTS),
+        ("allow-presentation", SANDBOXED_PRESENTATION),
+        ("allow-same-origin", SANDBOXED_ORIGIN),
+        ("allow-scripts", SANDBOXED_SCRIPTS | SANDBOXED_AUTOMATIC_FEATURES),
+        ("allow-top-navigation", SANDBOXED_TOP_LEVEL_NAVIGATION),
+    ];
+    for &(keyword, lifted) in keywords.iter() {
+        assert_eq!(parse_sandbox(keyword), SandboxFlags::all() - lifted);
+    }
+}
+
+#[test]
+pub fn test_process_whitespace() {
+    let input = "a  \n b";
+    assert_eq!(process_whitespace(input, WhiteSpaceMode::Normal), "a b");
+    assert_eq!(process_whitespace(input, WhiteSpaceMode::NoWrap), "a b");
+    assert_eq!(process_whitespace(input, WhiteSpaceMode::Pre), "a  \n b");
+    assert_eq!(process_whitespace(input, WhiteSpaceMode::PreWrap), "a  \n b");
+    assert_eq!(process_whitespace(input, WhiteSpaceMode::PreLine), "a\nb");
+
+    assert_eq!(process_whitespace("\t a\n\n\tb ", WhiteSpaceMode::PreLine), " a\n\nb ");
+    assert_eq!(process_whitespace("\t a\n\n\tb ", WhiteSpaceMode::Normal), " a b ");
+    assert_eq!(process_whitespace("", WhiteSpaceMode::Normal), "");
+}
+
+#[test]
+pub fn test_parse_month_string() {
+    assert_eq!(parse_month_string("2024-03"), Some((2024, 3)));
+    assert_eq!(parse_month_string("0001-12"), Some((1, 12)));
+    assert_eq!(parse_month_string("275760-09"), Some((275760, 9)));
+    assert_eq!(parse_month_string("2024-13"), None);
+    assert_eq!(parse_month_string("2024-00"), None);
+    assert_eq!(parse_month_string("0000-01"), None);
+    assert_eq!(parse_month_string("24-03"), None);
+    assert_eq!(parse_month_string("2024-3"), None);
+    assert_eq!(parse_month_string("2024-03-01"), None);
+    assert_eq!(parse_month_string(" 2024-03"), None);
+    assert_eq!(parse_month_string("2024-1\u{e9}"), None);
+    assert_eq!(parse_month_string(""), None);
+}
+
+#[test]
+pub fn test_domstring_eq_ignoring_chars() {
+    let s = DOMString::from("a b");
+    assert!(s.eq_ignoring_chars("ab", &[' ']));
+    assert!(s.eq_ignoring_chars(" a  b ", &[' ']));
+    assert!(!s.eq_ignoring_chars("ab", &[]));
+    assert!(!s.eq_ignoring_chars("abc", &[' ']));
+    assert!(DOMString::from("1-2\t3").eq_ignoring_chars("123", &['-', '\t']));
+    assert!(DOMString::from("  ").eq_ignoring_chars("", &[' ']));
+}
+
+#[test]
+pub fn test_parse_week_string() {
+    assert_eq!(parse_week_string("2024-W05"), Some((2024, 5)));
+    assert_eq!(parse_week_string("2024-W52"), Some((2024, 52)));
+    assert_eq!(parse_week_string("2024-W53"), None);
+    assert_eq!(parse_week_string("2015-W53"), Some((2015, 53)));
+    assert_eq!(parse_week_string("2020-W53"), Some((2020, 53)));
+    assert_eq!(parse_week_string("2026-W53"), Some((2026, 53)));
+    assert_eq!(parse_week_string("2020-W54"), None);
+    assert_eq!(parse_week_string("2024-W00"), None);
+    assert_eq!(parse_week_string("2024-W5"), None);
+    assert_eq!(parse_week_string("2024-w05"), None);
+    assert_eq!(parse_week_string("0000-W01"), None);
+    assert_eq!(parse_week_string("24-W05"), None);
+    assert_eq!(parse_week_string("2024-W05 "), None);
+}
+
+#[test]
+pub fn test_split_header_list() {
+    assert_eq!(split_header_list("a, \"b,c\", d"), vec!["a", "\"b,c\"", "d"]);
+    assert_eq!(split_header_list("text/html;q=0.9 ,\t*/*"), vec!["text/html;q=0.9", "*/*"]);
+    assert_eq!(split_header_list("x=\"a\\\",b\", y"), vec!["x=\"a\\\",b\"", "y"]);
+    assert_eq!(split_header_list(" , a,, b , "), vec!["a", "b"]);
+    assert_eq!(split_header_list("\"unterminated, still one"), vec!["\"unterminated, still one"]);
+    assert!(split_header_list("").is_empty());
+}
+
+#[test]
+pub fn test_rgba_u32_conversion() {
+    for &packed in &[0x00000000, 0xffffffff, 0xff336699, 0x80ff0000, 0x01020304] {
+        assert_eq!(rgba_to_u32(&rgba_from_u32(packed)), packed);
+    }
+
+    assert_eq!(rgba_to_u32(&RGBA { red: 1., green: 0.5, blue: 0., alpha: 1. }), 0xffff8000);
+    let just_below = 0.5 / 255. - 0.0001;
+    let just_above = 0.5 / 255. + 0.0001;
+    assert_eq!(rgba_to_u32(&RGBA { red: just_below, green: just_above, blue: 0., alpha: 0. }),
+               0x00000100);
+    assert_eq!(rgba_to_u32(&RGBA { red: 2., green: -1., blue: 0., alpha: 1. }), 0xffff0000);
+
+    let color = rgba_from_u32(0x80ff0000);
+    assert_eq!(color.red, 1.);
+    assert_eq!(color.green, 0.);
+    assert_eq!(color.alpha, 128. / 255.);
+}
+
+#[test]
+pub fn test_parse_time_string() {
+    assert_eq!(parse_time_string("23:59"), Some((23, 59, 0, 0)));
+    assert_eq!(parse_time_string("00:00:00"), Some((0, 0, 0, 0)));
+    assert_eq!(parse_time_string("12:00:30.500"), Some((12, 0, 30, 500)));
+    assert_eq!(parse_time_string("12:00:30.5"), Some((12, 0, 30, 500)));
+    assert_eq!(parse_time_string("12:00:30.05"), Some((12, 0, 30, 50)));
+    assert_eq!(parse_time_string("24:00"), None);
+    assert_eq!(parse_time_string("12:60"), None);
+    assert_eq!(parse_time_string("12:00:60"), None);
+    assert_eq!(parse_time_string("12:00:30."), None);
+    assert_eq!(parse_time_string("12:00:30.5000"), None);
+    assert_eq!(parse_time_string("1:00"), None);
+    assert_eq!(parse_time_string("12:00:"), None);
+    assert_eq!(parse_time_string("12:00 "), None);
+    assert_eq!(parse_time_string(""), None);
+}
+
+#[test]
+pub fn test_domstring_normalize_nfc() {
+    assert_eq!(DOMString::from("caf\u{65}\u{301}").normalize_nfc(), "caf\u{e9}");
+    assert_eq!(DOMString::from("caf\u{e9}").normalize_nfc(), "caf\u{e9}");
+    assert_eq!(DOMString::from("plain").normalize_nfc(), "plain");
+}
+
+#[test]
+pub fn test_truncate_with_ellipsis() {
+    assert_eq!(truncate_with_ellipsis("short", 10), "short");
+    assert_eq!(truncate_with_ellipsis("exact", 5), "exact");
+    assert_eq!(truncate_with_ellipsis("too long", 5), "too \u{2026}");
+    assert_eq!(truncate_with_ellipsis("\u{65e5}\u{672c}\u{8a9e}\u{306e}\u{30c6}\u{30ad}\u{30b9}\u{30c8}", 4),
+               "\u{65e5}\u{672c}\u{8a9e}\u{2026}");
+    assert_eq!(truncate_with_ellipsis("\u{65e5}\u{672c}\u{8a9e}", 3), "\u{65e5}\u{672c}\u{8a9e}");
+    assert_eq!(truncate_with_ellipsis("ab", 1), "\u{2026}");
+    assert_eq!(truncate_with_ellipsis("ab", 0), "");
+    assert_eq!(truncate_with_ellipsis("", 0), "");
+}
+
+#[test]
+pub fn test_parse_datetime_local() {
+    assert_eq!(parse_datetime_local("2024-03-15T13:30"), Some((2024, 3, 15, 13, 30, 0, 0)));
+    assert_eq!(parse_datetime_local("2024-03-15 13:30:05.25"), Some((2024, 3, 15, 13, 30, 5, 250)));
+    assert_eq!(parse_datetime_local("2024-02-29T00:00"), Some((2024, 2, 29, 0, 0, 0, 0)));
+    assert_eq!(parse_datetime_local("2023-02-29T00:00"), None);
+    assert_eq!(parse_datetime_local("2024-02-30T00:00"), None);
+    assert_eq!(parse_datetime_local("2024-04-31T00:00"), None);
+    assert_eq!(parse_datetime_local("2024-03-15t13:30"), None);
+    assert_eq!(parse_datetime_local("2024-03-15T24:00"), None);
+    assert_eq!(parse_datetime_local("2024-03-15"), None);
+    assert_eq!(parse_datetime_local("2024-03-15T13:30Z"), None);
+}
+
+#[test]
+pub fn test_parse_css_integer() {
+    assert!(is_css_integer("5"));
+    assert!(is_css_integer("+5"));
+    assert!(is_css_integer("-005"));
+    assert!(!is_css_integer(" 5"));
+    assert!(!is_css_integer("5 "));
+    assert!(!is_css_integer("5px"));
+    assert!(!is_css_integer("5.0"));
+    assert!(!is_css_integer("+"));
+    assert!(!is_css_integer("+-5"));
+    assert!(!is_css_integer(""));
+
+    assert_eq!(parse_css_integer("+5"), Some(5));
+    assert_eq!(parse_css_integer("-42"), Some(-42));
+    assert_eq!(parse_css_integer(" 5"), None);
+    assert_eq!(parse_css_integer("5px"), None);
+    assert_eq!(parse_css_integer("2147483648"), None);
+}
+
+#[test]
+pub fn test_domstring_content_hash() {
+    let hash = DOMString::from("display: none").content_hash();
+    assert_eq!(DOMString::from("display: none").content_hash(), hash);
+    assert!(DOMString::from("display: block").content_hash() != hash);
+
+    // FNV-1a is fixed, so so are its results.
+    assert_eq!(DOMString::new().content_hash(), 0xcbf29ce484222325);
+    assert_eq!(DOMString::from("a").content_hash(), 0xaf63dc4c8601ec8c);
+}
+
+#[test]
+pub fn test_parse_integer_list_into() {
+    let mut out = vec![7];
+    parse_integer_list_into("1, 2;3 \t-4,,", &mut out);
+    assert_eq!(out, vec![7, 1, 2, 3, -4]);
+
+    parse_integer_list_into("5px,x", &mut out);
+    assert_eq!(out, vec![7, 1, 2, 3, -4, 5, 0]);
+
+    out.clear();
+    parse_integer_list_into("", &mut out);
+    assert!(out.is_empty());
+}
+
+#[test]
+pub fn test_is_valid_form_field_name() {
+    assert!(is_valid_form_field_name("username"));
+    assert!(is_valid_form_field_name("field[0] \u{e9}"));
+    assert!(!is_valid_form_field_name(""));
+    assert!(!is_valid_form_field_name("user\nname"));
+    assert!(!is_valid_form_field_name("user\rname"));
+    assert!(!is_valid_form_field_name("\u{0}"));
+    assert!(!is_valid_form_field_name("\u{85}"));
+}
+
+#[test]
+pub fn test_rsplit_once_char() {
+    assert_eq!(rsplit_once_char("a.b.c", '.'), Some(("a.b", "c")));
+    assert_eq!(rsplit_once_char("image/svg+xml", '/'), Some(("image", "svg+xml")));
+    assert_eq!(rsplit_once_char("trailing.", '.'), Some(("trailing", "")));
+    assert_eq!(rsplit_once_char("\u{3b1}\u{2192}\u{3b2}\u{2192}\u{3b3}", '\u{2192}'),
+               Some(("\u{3b1}\u{2192}\u{3b2}", "\u{3b3}")));
+    assert_eq!(rsplit_once_char("none", '.'), None);
+}
+
+#[test]
+pub fn test_decode_named_char_refs() {
+    assert_eq!(decode_named_char_refs("&amp;&lt;&unknown;"), "&<&unknown;");
+    assert_eq!(decode_named_char_refs("a &quot;b&quot; &apos;c&apos;"), "a \"b\" 'c'");
+    assert_eq!(decode_named_char_refs("&copy; 2016&nbsp;x"), "\u{a9} 2016\u{a0}x");
+    assert_eq!(decode_named_char_refs("&amp &AMP; &amp;amp;"), "&amp &AMP; &amp;");
+    assert_eq!(decode_named_char_refs("&#38; & &; &&gt;"), "&#38; & &; &>");
+    assert_eq!(decode_named_char_refs("trailing &"), "trailing &");
+    assert_eq!(decode_named_char_refs("&\u{e9}a;"), "&\u{e9}a;");
+    assert_eq!(decode_named_char_refs(""), "");
+}
+
+#[test]
+pub fn test_best_float_representation() {
+    assert_eq!(best_float_representation(1.0), "1");
+    assert_eq!(best_float_representation(0.5), "0.5");
+    assert_eq!(best_float_representation(-1.5), "-1.5");
+    assert_eq!(best_float_representation(123.456), "123.456");
+    assert_eq!(best_float_representation(0.1 + 0.2), "0.30000000000000004");
+    assert_eq!(best_float_representation(1e20), "100000000000000000000");
+    assert_eq!(best_float_representation(1e21), "1e+21");
+    assert_eq!(best_float_representation(1.5e300), "1.5e+300");
+    assert_eq!(best_float_representation(0.000001), "0.000001");
+    assert_eq!(best_float_representation(1e-7), "1e-7");
+    assert_eq!(best_float_representation(-2.5e-10), "-2.5e-10");
+    assert_eq!(best_float_representation(0.), "0");
+    assert_eq!(best_float_representation(-0.), "0");
+    assert_eq!(best_float_representation(::std::f64::NAN), "NaN");
+    assert_eq!(best_float_representation(::std::f64::NEG_INFINITY), "-Infinity");
+}
+
+#[test]
+pub fn test_split_words() {
+    assert_eq!(split_words("hello, world!").collect::<Vec<_>>(), vec!["hello", "world"]);
+    assert_eq!(split_words("  don't\tstop\u{2014}now ").collect::<Vec<_>>(), vec!["don", "t", "stop", "now"]);
+    assert_eq!(split_words("caf\u{e9} 2016 \u{65e5}\u{672c}").collect::<Vec<_>>(),
+               vec!["caf\u{e9}", "2016", "\u{65e5}\u{672c}"]);
+    assert_eq!(split_words("...").count(), 0);
+    assert_eq!(split_words("").count(), 0);
+}
+
+#[test]
+pub fn test_domstring_shrink_to_fit() {
+    let mut s = DOMString::new();
+    for _ in 0..1000 {
+        s.push_str("text");
+    }
+    assert!(s.capacity() >= 4000);
+
+    s.clear();
+    s.push_str("short");
+    assert!(s.capacity() >= 4000);
+    s.shrink_to_fit();
+    assert!(s.capacity() < 4000);
+    assert_eq!(s, "short");
+}
+
+#[test]
+pub fn test_parse_css_dimension() {
+    assert_eq!(parse_css_dimension("10px"), Some((10., "px".to_owned())));
+    assert_eq!(parse_css_dimension("1.5em"), Some((1.5, "em".to_owned())));
+    assert_eq!(parse_css_dimension("50%"), Some((50., "%".to_owned())));
+    assert_eq!(parse_css_dimension("-.5EM"), Some((-0.5, "EM".to_owned())));
+    assert_eq!(parse_css_dimension("1e3px"), Some((1000., "px".to_owned())));
+    assert_eq!(parse_css_dimension("2e-1vw"), Some((0.2, "vw".to_owned())));
+    assert_eq!(parse_css_dimension("10"), None);
+    assert_eq!(parse_css_dimension("10 px"), None);
+    assert_eq!(parse_css_dimension("10px "), None);
+    assert_eq!(parse_css_dimension("10%%"), None);
+    assert_eq!(parse_css_dimension("1.px"), None);
+    assert_eq!(parse_css_dimension("px"), None);
+    assert_eq!(parse_css_dimension(""), None);
+}
+
+#[test]
+pub fn test_contains_bidi_control() {
+    assert!(!contains_bidi_control("https://example.com/exe.txt"));
+    assert!(!contains_bidi_control("\u{5e9}\u{5dc}\u{5d5}\u{5dd} \u{200f}"));
+    assert!(contains_bidi_control("https://example.com/\u{202e}txt.exe"));
+    assert!(contains_bidi_control("\u{202a}"));
+    assert!(contains_bidi_control("a\u{2069}"));
+    assert!(!contains_bidi_control(""));
+}
+
+#[test]
+pub fn test_parse_quoted_directive_list() {
+    assert_eq!(parse_quoted_directive_list("\"cache\", \"cookies\""), vec!["cache", "cookies"]);
+    assert_eq!(parse_quoted_directive_list("\"a,b\",\"c\\\"d\""), vec!["a,b", "c\"d"]);
+    assert_eq!(parse_quoted_directive_list("cache, \"storage\" x, \"*\", \"open"), vec!["*"]);
+    assert!(parse_quoted_directive_list("").is_empty());
+}
+
+#[test]
+pub fn test_domstring_form_urlencode() {
+    assert_eq!(DOMString::from("a b&c").form_urlencode(), "a+b%26c");
+    assert_eq!(DOMString::from("*-._~+=").form_urlencode(), "*-._%7E%2B%3D");
+    assert_eq!(DOMString::from("\u{e9}\n").form_urlencode(), "%C3%A9%0A");
+    assert_eq!(DOMString::new().form_urlencode(), "");
+}
+
+#[test]
+pub fn test_canonicalize_mime_essence() {
+    assert_eq!(canonicalize_mime_essence("Text/HTML").map(|essence| essence.to_string()),
+               Some("text/html".to_owned()));
+    assert_eq!(canonicalize_mime_essence(" application/JSON ; charset=utf-8")
+                   .map(|essence| essence.to_string()),
+               Some("application/json".to_owned()));
+    assert!(canonicalize_mime_essence("text/").is_none());
+    assert!(canonicalize_mime_essence("/html").is_none());
+    assert!(canonicalize_mime_essence("text").is_none());
+    assert!(canonicalize_mime_essence("text/html/x").is_none());
+    assert!(canonicalize_mime_essence("te xt/html").is_none());
+    assert!(canonicalize_mime_essence("").is_none());
+}
+
+#[test]
+pub fn test_char_indices_utf16() {
+    assert_eq!(char_indices_utf16("ab").collect::<Vec<_>>(), vec![(0, 'a'), (1, 'b')]);
+    assert_eq!(char_indices_utf16("a\u{1f600}b\u{10000}\u{e9}").collect::<Vec<_>>(),
+               vec![(0, 'a'), (1, '\u{1f600}'), (3, 'b'), (4, '\u{10000}'), (6, '\u{e9}')]);
+    assert_eq!(char_indices_utf16("").next(), None);
+}
+
+#[test]
+pub fn test_header_is_nosniff() {
+    assert!(header_is_nosniff("nosniff"));
+    assert!(header_is_nosniff("NOSNIFF, other"));
+    assert!(header_is_nosniff("other ,\tNoSniff "));
+    assert!(!header_is_nosniff("sniff"));
+    assert!(!header_is_nosniff("nosniff-please"));
+    assert!(!header_is_nosniff(""));
+}
+
+#[test]
+pub fn test_domstring_escape_js_string() {
+    assert_eq!(DOMString::from("say \"hi\"\nit's").escape_js_string(), "say \\\"hi\\\"\\nit\\'s");
+    assert_eq!(DOMString::from("C:\\dir\r\n").escape_js_string(), "C:\\\\dir\\r\\n");
+    assert_eq!(DOMString::from("\t\u{0}\u{7f}").escape_js_string(), "\\u0009\\u0000\\u007F");
+    assert_eq!(DOMString::from("a\u{2028}b").escape_js_string(), "a\\u2028b");
+    assert_eq!(DOMString::from("plain \u{e9}").escape_js_string(), "plain \u{e9}");
+}
+
+#[test]
+pub fn test_parse_css_number() {
+    assert_eq!(parse_css_number("-1.5e2"), Some(-150.));
+    assert_eq!(parse_css_number(".5"), Some(0.5));
+    assert_eq!(parse_css_number("+3"), Some(3.));
+    assert_eq!(parse_css_number("1E+2"), Some(100.));
+    assert_eq!(parse_css_number("1."), None);
+    assert_eq!(parse_css_number("1e"), None);
+    assert_eq!(parse_css_number("1px"), None);
+    assert_eq!(parse_css_number(" 1"), None);
+    assert_eq!(parse_css_number("."), None);
+    assert_eq!(parse_css_number("-"), None);
+    assert_eq!(parse_css_number(""), None);
+}
+
+#[test]
+pub fn test_as_ascii() {
+    assert_eq!(as_ascii("abc 123"), Some(&b"abc 123"[..]));
+    assert_eq!(as_ascii(""), Some(&b""[..]));
+    assert_eq!(as_ascii("caf\u{e9}"), None);
+    assert_eq!(as_ascii("\u{7f}"), Some(&b"\x7f"[..]));
+}
+
+#[test]
+pub fn test_content_disposition_filename() {
+    assert_eq!(content_disposition_filename("attachment; filename=\"a.txt\""),
+               Some("a.txt".to_owned()));
+    assert_eq!(content_disposition_filename("attachment; filename*=UTF-8''a%20b.txt"),
+               Some("a b.txt".to_owned()));
+    assert_eq!(content_disposition_filename("attachment; filename*=utf-8'en'%E2%82%AC.txt; filename=x"),
+               Some("\u{20ac}.txt".to_owned()));
+    assert_eq!(content_disposition_filename("inline;FILENAME=\"semi;colon \\\"q\\\".txt\""),
+               Some("semi;colon \"q\".txt".to_owned()));
+    assert_eq!(content_disposition_filename("attachment; filename*=iso-8859-1''caf%E9"),
+               Some("caf\u{e9}".to_owned()));
+    assert_eq!(content_disposition_filename("attachment; filename*=koi8-r''x; filename=plain.txt"),
+               Some("plain.txt".to_owned()));
+    assert_eq!(content_disposition_filename("attachment; size=10; filename = token.bin "),
+               Some("token.bin".to_owned()));
+    assert_eq!(content_disposition_filename("attachment"), None);
+    assert_eq!(content_disposition_filename("attachment; filename=\"unterminated"), None);
+    assert_eq!(content_disposition_filename("bad type; filename=a.txt"), None);
+}
+
+#[test]
+pub fn test_domstring_split_to_dom_strings() {
+    let pieces = DOMString::from("a,b,c").split_to_dom_strings(',');
+    assert_eq!(pieces, vec![DOMString::from("a"), DOMString::from("b"), DOMString::from("c")]);
+    assert_eq!(DOMString::from(",x,").split_to_dom_strings(','),
+               vec![DOMString::new(), DOMString::from("x"), DOMString::new()]);
+    assert_eq!(DOMString::from("\u{3b1}\u{2192}\u{3b2}").split_to_dom_strings('\u{2192}'),
+               vec![DOMString::from("\u{3b1}"), DOMString::from("\u{3b2}")]);
+    assert_eq!(DOMString::new().split_to_dom_strings(','), vec![DOMString::new()]);
+}
+
+#[test]
+pub fn test_decode_fragment() {
+    assert_eq!(decode_fragment("top"), "top");
+    assert_eq!(decode_fragment("%E2%9C%93"), "\u{2713}");
+    assert_eq!(decode_fragment("a%20b%"), "a b%");
+    assert_eq!(decode_fragment("%FF"), "\u{fffd}");
+    assert_eq!(decode_fragment(""), "");
+}
+
+#[test]
+pub fn test_parse_css_string() {
+    assert_eq!(parse_css_string("\"a\\\nb\""), Some(("ab".to_owned(), "")));
+    assert_eq!(parse_css_string("'it\\'s' rest"), Some(("it's".to_owned(), " rest")));
+    assert_eq!(parse_css_string("\"say \\\"hi\\\"\";"), Some(("say \"hi\"".to_owned(), ";")));
+    assert_eq!(parse_css_string("\"\\26 x\""), Some(("&x".to_owned(), "")));
+    assert_eq!(parse_css_string("\"a'b\""), Some(("a'b".to_owned(), "")));
+    assert_eq!(parse_css_string("\"a\\\r\nb\""), Some(("ab".to_owned(), "")));
+    assert_eq!(parse_css_string("\"unterminated"), Some(("unterminated".to_owned(), "")));
+    assert_eq!(parse_css_string("\"trailing\\"), Some(("trailing".to_owned(), "")));
+    assert_eq!(parse_css_string("\"bad\nstring\""), None);
+    assert_eq!(parse_css_string("unquoted"), None);
+    assert_eq!(parse_css_string(""), None);
+}
+
+#[test]
+pub fn test_is_ascii_lowercase() {
+    assert!(is_ascii_lowercase("abc"));
+    assert!(is_ascii_lowercase("data-x_1 \u{e9}"));
+    assert!(is_ascii_lowercase("\u{c9}"));
+    assert!(is_ascii_lowercase(""));
+    assert!(!is_ascii_lowercase("Abc"));
+    assert!(!is_ascii_lowercase("abZ"));
+}
+
+#[test]
+pub fn test_domstring_eq_ignore_ascii_case() {
+    assert!(DOMString::from("Div").eq_ignore_ascii_case("div"));
+    assert!(DOMString::from("DIV").eq_ignore_ascii_case("dIv"));
+    assert!(!DOMString::from("div").eq_ignore_ascii_case("span"));
+    assert!(!DOMString::from("div").eq_ignore_ascii_case("divs"));
+    assert!(!DOMString::from("\u{c9}").eq_ignore_ascii_case("\u{e9}"));
+}
+
+#[test]
+pub fn test_parse_refresh() {
+    assert_eq!(parse_refresh("5"), Some((5, None)));
+    assert_eq!(parse_refresh("5; url=x"), Some((5, Some("x".to_owned()))));
+    assert_eq!(parse_refresh(" 0 , URL = 'next.html' trailing"), Some((0, Some("next.html".to_owned()))));
+    assert_eq!(parse_refresh("3.5;\"quoted.html"), Some((3, Some("quoted.html".to_owned()))));
+    assert_eq!(parse_refresh(".5; /page"), Some((0, Some("/page".to_owned()))));
+    assert_eq!(parse_refresh("1;urlish.html"), Some((1, Some("urlish.html".to_owned()))));
+    assert_eq!(parse_refresh("1; url="), Some((1, None)));
+    assert_eq!(parse_refresh("soon; url=x"), None);
+    assert_eq!(parse_refresh(""), None);
+}
+
+#[test]
+pub fn test_parse_refresh_duration() {
+    assert_eq!(parse_refresh_duration("5; url=x"), Some((Duration::from_secs(5), Some("x".to_owned()))));
+    assert_eq!(parse_refresh_duration("0"), Some((Duration::from_secs(0), None)));
+    assert_eq!(parse_refresh_duration("x"), None);
+}
+
+#[test]
+pub fn test_normalize_browsing_context_name() {
+    assert_eq!(normalize_browsing_context_name("_BLANK"), BrowsingContextName::Blank);
+    assert_eq!(normalize_browsing_context_name("_self"), BrowsingContextName::Self_);
+    assert_eq!(normalize_browsing_context_name(""), BrowsingContextName::Self_);
+    assert_eq!(normalize_browsing_context_name("_Parent"), BrowsingContextName::Parent);
+    assert_eq!(normalize_browsing_context_name("_top"), BrowsingContextName::Top);
+    assert_eq!(normalize_browsing_context_name("_unknown"),
+               BrowsingContextName::Named("_unknown".to_owned()));
+    assert_eq!(normalize_browsing_context_name("myFrame"),
+               BrowsingContextName::Named("myFrame".to_owned()));
+}
+
+#[test]
+pub fn test_count_hard_lines() {
+    assert_eq!(count_hard_lines("a\nb\n"), 2);
+    assert_eq!(count_hard_lines("a\nb"), 2);
+    assert_eq!(count_hard_lines("abc"), 1);
+    assert_eq!(count_hard_lines("\n\n"), 2);
+    assert_eq!(count_hard_lines("a\r\nb"), 2);
+    assert_eq!(count_hard_lines(""), 0);
+}
+
+#[test]
+pub fn test_parse_accesskeys() {
+    assert_eq!(parse_accesskeys("a b cd"), vec!['a', 'b']);
+    assert_eq!(parse_accesskeys("\t\u{e9}\n1  ab\u{301} x"), vec!['\u{e9}', '1', 'x']);
+    assert!(parse_accesskeys("").is_empty());
+    assert!(parse_accesskeys("long keys").is_empty());
+}
+
+#[test]
+pub fn test_domstring_debug_truncated() {
+    let s = DOMString::from("line \"one\"\nline two, which goes on and on");
+    assert_eq!(s.debug_truncated(14), "\"line \\\"one\\\"\\nli\u{2026}\"");
+    assert_eq!(DOMString::from("short").debug_truncated(14), "\"short\"");
+    assert_eq!(DOMString::new().debug_truncated(0), "\"\"");
+}
+
+#[test]
+pub fn test_parse_integer_radix() {
+    assert_eq!(parse_integer_radix("0xFF"), Some(255));
+    assert_eq!(parse_integer_radix("-0b101"), Some(-5));
+    assert_eq!(parse_integer_radix("42"), Some(42));
+    assert_eq!(parse_integer_radix("+0O17"), Some(15));
+    assert_eq!(parse_integer_radix("0"), Some(0));
+    assert_eq!(parse_integer_radix("007"), Some(7));
+    assert_eq!(parse_integer_radix("-0x8000000000000000"), Some(i64::min_value()));
+    assert_eq!(parse_integer_radix("0x8000000000000000"), None);
+    assert_eq!(parse_integer_radix("0x"), None);
+    assert_eq!(parse_integer_radix("0b102"), None);
+    assert_eq!(parse_integer_radix("0x+1"), None);
+    assert_eq!(parse_integer_radix(" 42"), None);
+    assert_eq!(parse_integer_radix("42px"), None);
+    assert_eq!(parse_integer_radix("-"), None);
+}
+
+#[test]
+pub fn test_split_first_whitespace() {
+    assert_eq!(split_first_whitespace("  foo   bar baz"), ("foo", "bar baz"));
+    assert_eq!(split_first_whitespace("foo\tbar\n"), ("foo", "bar\n"));
+    assert_eq!(split_first_whitespace("foo"), ("foo", ""));
+    assert_eq!(split_first_whitespace("  "), ("", ""));
+    assert_eq!(split_first_whitespace(""), ("", ""));
+}
+
+#[test]
+pub fn test_simple_color() {
+    assert!(is_valid_simple_color("#aabbcc"));
+    assert!(is_valid_simple_color("#AaBb09"));
+    assert!(!is_valid_simple_color("#abc"));
+    assert!(!is_valid_simple_color("aabbcc"));
+    assert!(!is_valid_simple_color("#aabbccdd"));
+    assert!(!is_valid_simple_color("#aabbcg"));
+    assert!(!is_valid_simple_color("#aabbc\u{e9}"));
+
+    assert_eq!(sanitize_simple_color("#AABBCC"), "#aabbcc");
+    assert_eq!(sanitize_simple_color("#abc"), "#000000");
+    assert_eq!(sanitize_simple_color("red"), "#000000");
+    assert_eq!(sanitize_simple_color(""), "#000000");
+}
+
+#[test]
+pub fn test_domstring_pad_start() {
+    assert_eq!(DOMString::from("5").pad_start(2, '0'), "05");
+    assert_eq!(DOMString::from("2016").pad_start(6, '0'), "002016");
+    assert_eq!(DOMString::from("123").pad_start(2, '0'), "123");
+    assert_eq!(DOMString::from("\u{e9}").pad_start(1, ' '), "\u{e9}");
+    assert_eq!(DOMString::from("x").pad_start(3, '\u{b7}'), "\u{b7}\u{b7}x");
+    assert_eq!(DOMString::new().pad_start(0, '0'), "");
+}
+
+#[test]
+pub fn test_parse_date_string() {
+    assert_eq!(parse_date_string("2024-02-29"), Some((2024, 2, 29)));
+    assert_eq!(parse_date_string("2000-02-29"), Some((2000, 2, 29)));
+    assert_eq!(parse_date_string("2024-12-31"), Some((2024, 12, 31)));
+    assert_eq!(parse_date_string("2023-02-29"), None);
+    assert_eq!(parse_date_string("1900-02-29"), None);
+    assert_eq!(parse_date_string("2024-13-01"), None);
+    assert_eq!(parse_date_string("2024-06-31"), None);
+    assert_eq!(parse_date_string("2024-06-00"), None);
+    assert_eq!(parse_date_string("2024-6-01"), None);
+    assert_eq!(parse_date_string("2024-06-01T00:00"), None);
+    assert_eq!(parse_date_string(""), None);
+}
+
+#[test]
+pub fn test_strip_prefix_ignore_ascii_case() {
+    assert_eq!(strip_prefix_ignore_ascii_case("JAVAScript:alert()", "javascript:"), Some("alert()"));
+    assert_eq!(strip_prefix_ignore_ascii_case("data:", "DATA:"), Some(""));
+    assert_eq!(strip_prefix_ignore_ascii_case("\u{e9}t\u{e9}", "\u{e9}"), Some("t\u{e9}"));
+    assert_eq!(strip_prefix_ignore_ascii_case("\u{c9}t\u{e9}", "\u{e9}"), None);
+    assert_eq!(strip_prefix_ignore_ascii_case("java", "javascript:"), None);
+    assert_eq!(strip_prefix_ignore_ascii_case("vbscript:x", "javascript:"), None);
+    assert_eq!(strip_prefix_ignore_ascii_case("anything", ""), Some("anything"));
+}
+
+#[test]
+pub fn test_parse_float_list() {
+    assert_eq!(parse_float_list("1, 2.5 3e1"), vec![1.0, 2.5, 30.0]);
+    assert_eq!(parse_float_list(" -1,,.5\t+2 "), vec![-1.0, 0.5, 2.0]);
+    assert_eq!(parse_float_list("1px, 2, x, 3."), vec![2.0]);
+    assert!(parse_float_list("").is_empty());
+}
+
+#[test]
+pub fn test_domstring_substring_data() {
+    let s = DOMString::from("h\u{e9}llo");
+    assert_eq!(s.substring_data(0, 2), Some(DOMString::from("h\u{e9}")));
+    assert_eq!(s.substring_data(1, 100), Some(DOMString::from("\u{e9}llo")));
+    assert_eq!(s.substring_data(0, 0), Some(DOMString::new()));
+    assert_eq!(s.substring_data(5, 1), Some(DOMString::new()));
+    assert_eq!(s.substring_data(6, 1), None);
+    assert_eq!(DOMString::new().substring_data(0, 1), Some(DOMString::new()));
+}
+
+#[test]
+pub fn test_accessible_name_from_string() {
+    assert_eq!(accessible_name_from_string("   "), None);
+    assert_eq!(accessible_name_from_string(""), None);
+    assert_eq!(accessible_name_from_string("  a  b "), Some(DOMString::from("a b")));
+    assert_eq!(accessible_name_from_string("\tClose\n dialog"), Some(DOMString::from("Close dialog")));
+}
+
+#[test]
+pub fn test_parse_cache_control() {
+    fn directives(value: &str) -> Vec<(String, Option<String>)> {
+        parse_cache_control(value).into_iter().map(|(name, argument)| (name.to_string(), argument)).collect()
+    }
+
+    assert_eq!(directives("max-age=600, no-cache, private"),
+               vec![("max-age".to_owned(), Some("600".to_owned())),
+                    ("no-cache".to_owned(), None),
+                    ("private".to_owned(), None)]);
+    assert_eq!(directives("Private=\"Set-Cookie, X-Foo\" , MAX-AGE = 0"),
+               vec![("private".to_owned(), Some("Set-Cookie, X-Foo".to_owned())),
+                    ("max-age".to_owned(), Some("0".to_owned()))]);
+    assert_eq!(directives("=5, ,ext=\"a\\\"b\""),
+               vec![("ext".to_owned(), Some("a\"b".to_owned()))]);
+    assert!(directives("").is_empty());
+}
+
+#[test]
+pub fn test_resolve_two_digit_year() {
+    assert_eq!(resolve_two_digit_year(94, 2016), 1994);
+    assert_eq!(resolve_two_digit_year(20, 2016), 2020);
+    assert_eq!(resolve_two_digit_year(66, 2016), 2066);
+    assert_eq!(resolve_two_digit_year(67, 2016), 1967);
+    assert_eq!(resolve_two_digit_year(16, 2016), 2016);
+    assert_eq!(resolve_two_digit_year(5, 2090), 2105);
+    assert_eq!(resolve_two_digit_year(41, 2090), 2041);
+    assert_eq!(resolve_two_digit_year(40, 2090), 2140);
+    assert_eq!(resolve_two_digit_year(99, 2000), 1999);
+}